@@ -0,0 +1,9 @@
+<?xml version="1.0" encoding="UTF-8"?>
+<tileset version="1.10" tiledversion="1.11.2" name="test" tilewidth="16" tileheight="16" tilecount="4096" columns="64">
+ <image source="../textures/test.png" width="1024" height="1024"/>
+ <tile id="2275">
+  <objectgroup draworder="index" id="2">
+   <object id="2" x="0.0472352" y="0.0761759" width="11.0049" height="15.898"/>
+  </objectgroup>
+ </tile>
+</tileset>
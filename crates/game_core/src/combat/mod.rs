@@ -0,0 +1,241 @@
+#![coverage(off)]
+
+pub mod projectile;
+
+use bevy::prelude::*;
+use crate::combat::projectile::{load_projectile_definitions, FireProjectile, Projectile, ProjectileDefinitions, ProjectilePool};
+
+/// Collision group of attack hitboxes. Character controllers ignore it so a
+/// hitbox never blocks a movement.
+pub const HITBOX_COLLISION_GROUP: u32 = 1 << 2;
+
+pub struct CombatModule;
+
+impl Plugin for CombatModule {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.register_type::<Attacker>();
+        app.register_type::<Hurtbox>();
+        app.register_type::<Hitbox>();
+        app.register_type::<HitStop>();
+        app.register_type::<DamageNumber>();
+        app.add_event::<AttackStarted>();
+        app.add_event::<HitConfirmed>();
+
+        app.register_type::<Projectile>();
+        app.init_resource::<ProjectileDefinitions>();
+        app.init_resource::<ProjectilePool>();
+        app.add_event::<FireProjectile>();
+        app.add_systems(Startup, load_projectile_definitions);
+    }
+}
+
+/// Side of an entity in a fight. Hitboxes only hit hurtboxes of another team.
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Team {
+    #[default]
+    Player,
+    Enemy,
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackPhase {
+    Startup,
+    Active,
+    Recovery,
+    Finished,
+}
+
+/// Timing and shape of a melee attack. The timing is given in frames of the
+/// attack animation clip so the hitbox is only active while the swing is shown.
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub struct AttackDefinition {
+    pub frame_duration: f32,
+    pub startup_frames: u32,
+    pub active_frames: u32,
+    pub recovery_frames: u32,
+    /// Center of the hitbox relative to the attacker, facing right.
+    pub offset: Vec2,
+    pub half_size: Vec2,
+    /// Multiplier of the damage of the [`Attacker`].
+    pub damage_scale: f32,
+    pub hit_stop: f32,
+    pub knockback: f32,
+}
+
+impl Default for AttackDefinition {
+    fn default() -> Self {
+        Self {
+            frame_duration: 0.08,
+            startup_frames: 1,
+            active_frames: 2,
+            recovery_frames: 1,
+            offset: Vec2::new(14.0, 0.0),
+            half_size: Vec2::new(9.0, 12.0),
+            damage_scale: 1.0,
+            hit_stop: 0.08,
+            knockback: 120.0,
+        }
+    }
+}
+
+impl AttackDefinition {
+
+    pub fn frame_count(&self) -> u32 {
+        self.startup_frames + self.active_frames + self.recovery_frames
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.frame_count() as f32 * self.frame_duration
+    }
+
+    /// Returns the phase of the attack `elapsed` seconds after it was started.
+    pub fn phase(&self, elapsed: f32) -> AttackPhase {
+        let frame = (elapsed / self.frame_duration.max(f32::EPSILON)) as u32;
+        if frame < self.startup_frames {
+            AttackPhase::Startup
+        } else if frame < self.startup_frames + self.active_frames {
+            AttackPhase::Active
+        } else if frame < self.frame_count() {
+            AttackPhase::Recovery
+        } else {
+            AttackPhase::Finished
+        }
+    }
+
+    /// Damage dealt by this attack for the given base damage.
+    pub fn damage(&self, base_damage: i32) -> i32 {
+        (base_damage as f32 * self.damage_scale).round().max(0.0) as i32
+    }
+}
+
+/// An entity which can perform melee attacks. Set `requested` to start an attack,
+/// it is ignored while an attack is running.
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct Attacker {
+    pub team: Team,
+    pub attack: AttackDefinition,
+    /// Base damage, e.g. taken from the stats of the player.
+    pub damage: i32,
+    /// `-1.0` for left and `1.0` for right.
+    pub facing: f32,
+    pub requested: bool,
+    /// Seconds since the running attack started.
+    pub elapsed: Option<f32>,
+    pub hitbox: Option<Entity>,
+}
+
+impl Attacker {
+
+    pub fn new(team: Team, attack: AttackDefinition, damage: i32) -> Self {
+        Self {
+            team,
+            attack,
+            damage,
+            facing: 1.0,
+            ..default()
+        }
+    }
+
+    pub fn is_attacking(&self) -> bool {
+        self.elapsed.is_some()
+    }
+}
+
+/// Marks an entity whose collider can be hit by a [`Hitbox`].
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component)]
+pub struct Hurtbox {
+    pub team: Team,
+}
+
+/// Sensor spawned during the active frames of an attack. Every target is hit
+/// only once per attack.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Hitbox {
+    pub owner: Entity,
+    pub team: Team,
+    pub damage: i32,
+    pub hit_stop: f32,
+    pub knockback: f32,
+    pub hits: Vec<Entity>,
+}
+
+/// Freezes the movement and the attack of an entity for a short time after a hit.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component)]
+pub struct HitStop {
+    pub remaining: f32,
+}
+
+/// Floating text showing the damage of a hit.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component)]
+pub struct DamageNumber {
+    pub lifetime: f32,
+    pub remaining: f32,
+}
+
+/// Send when an attacker starts an attack.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AttackStarted {
+    pub attacker: Entity,
+    pub team: Team,
+}
+
+/// Send when a hitbox hits a hurtbox of another team.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HitConfirmed {
+    pub attacker: Entity,
+    pub target: Entity,
+    pub damage: i32,
+    pub knockback: f32,
+    /// `-1.0` if the target is pushed to the left and `1.0` to the right.
+    pub direction: f32,
+    pub position: Vec2,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attack() -> AttackDefinition {
+        AttackDefinition {
+            frame_duration: 0.25,
+            startup_frames: 1,
+            active_frames: 2,
+            recovery_frames: 1,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_attack_phase_boundaries() {
+        let attack = attack();
+        assert_eq!(attack.duration(), 1.0);
+        assert_eq!(attack.phase(0.0), AttackPhase::Startup);
+        assert_eq!(attack.phase(0.24), AttackPhase::Startup);
+        assert_eq!(attack.phase(0.25), AttackPhase::Active);
+        assert_eq!(attack.phase(0.74), AttackPhase::Active);
+        assert_eq!(attack.phase(0.75), AttackPhase::Recovery);
+        assert_eq!(attack.phase(0.99), AttackPhase::Recovery);
+        assert_eq!(attack.phase(1.0), AttackPhase::Finished);
+    }
+
+    #[test]
+    fn test_attack_phase_without_frame_duration() {
+        let attack = AttackDefinition { frame_duration: 0.0, ..attack() };
+        assert_eq!(attack.phase(0.0), AttackPhase::Startup);
+        assert_eq!(attack.phase(0.01), AttackPhase::Finished);
+    }
+
+    #[test]
+    fn test_attack_phase_without_startup() {
+        let attack = AttackDefinition { startup_frames: 0, ..attack() };
+        assert_eq!(attack.phase(0.0), AttackPhase::Active);
+        assert_eq!(attack.phase(0.5), AttackPhase::Recovery);
+    }
+}
@@ -0,0 +1,103 @@
+#![coverage(off)]
+
+use std::collections::HashMap;
+use bevy::prelude::*;
+use serde::Deserialize;
+use crate::combat::Team;
+use crate::config::GlobalConfig;
+use crate::stats::StatusEffect;
+
+pub const PROJECTILES_CONFIG_PATH: &str = "config/projectiles.toml";
+
+/// Data of one projectile kind, loaded from `config/projectiles.toml`.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ProjectileDefinition {
+    pub speed: f32,
+    /// Downward acceleration in pixel per second², `0.0` flies straight.
+    pub gravity: f32,
+    /// Seconds until the projectile is returned to the pool.
+    pub lifetime: f32,
+    /// Number of hurtboxes the projectile passes through before it stops.
+    pub pierce: u32,
+    pub damage: i32,
+    pub knockback: f32,
+    pub radius: f32,
+    pub color: [f32; 3],
+    /// Effect which is applied on every hit target, e.g. poison or slow.
+    pub effect: Option<StatusEffect>,
+}
+
+impl Default for ProjectileDefinition {
+    fn default() -> Self {
+        Self {
+            speed: 300.0,
+            gravity: 0.0,
+            lifetime: 2.0,
+            pierce: 0,
+            damage: 10,
+            knockback: 60.0,
+            radius: 2.0,
+            color: [1.0, 1.0, 1.0],
+            effect: None,
+        }
+    }
+}
+
+/// All projectile kinds by name.
+#[derive(Resource, Deserialize, Clone, Debug, Default)]
+#[serde(transparent)]
+pub struct ProjectileDefinitions(pub HashMap<String, ProjectileDefinition>);
+
+impl ProjectileDefinitions {
+
+    pub fn get(&self, kind: &str) -> Option<&ProjectileDefinition> {
+        self.0.get(kind)
+    }
+}
+
+/// A flying projectile. Projectiles are pooled, an inactive projectile is hidden
+/// and waits in the [`ProjectilePool`] for the next shot.
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct Projectile {
+    pub kind: String,
+    pub owner: Option<Entity>,
+    pub team: Team,
+    pub velocity: Vec2,
+    pub gravity: f32,
+    pub remaining: f32,
+    pub pierce_left: u32,
+    pub damage: i32,
+    pub knockback: f32,
+    /// Hurtboxes which were already hit by this shot.
+    pub hits: Vec<Entity>,
+    pub active: bool,
+}
+
+/// Inactive projectile entities which can be reused.
+#[derive(Resource, Debug, Default)]
+pub struct ProjectilePool {
+    pub free: Vec<Entity>,
+}
+
+/// Request to fire a projectile of the given kind.
+#[derive(Event, Debug, Clone)]
+pub struct FireProjectile {
+    pub owner: Option<Entity>,
+    pub team: Team,
+    pub kind: String,
+    pub position: Vec2,
+    pub direction: Vec2,
+}
+
+#[coverage(off)]
+pub(crate) fn load_projectile_definitions(mut definitions: ResMut<ProjectileDefinitions>) {
+    match GlobalConfig::try_load::<ProjectileDefinitions>(PROJECTILES_CONFIG_PATH) {
+        Ok(loaded) => {
+            info!("Loaded {} projectile definitions", loaded.0.len());
+            *definitions = loaded;
+        }
+        Err(error) => error!("{}", error),
+    }
+}
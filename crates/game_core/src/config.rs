@@ -1,10 +1,25 @@
 #![coverage(off)]
 
-use std::fs::{read_to_string, write};
+use std::fs::{metadata, read_to_string, write};
 use std::path::Path;
+use std::time::SystemTime;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use crate::key_converter::convert;
+use crate::tiled::properties::PropertyValueExt;
+
+pub const GAMEPLAY_CONFIG_PATH: &str = "config/gameplay.toml";
+
+pub struct ConfigModule;
+
+impl Plugin for ConfigModule {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameplayConfigWatcher>();
+        app.add_systems(Update, reload_gameplay_config.run_if(resource_exists::<GlobalConfig>));
+    }
+}
 
 // =================================================================================================
 //
@@ -16,6 +31,7 @@ use crate::key_converter::convert;
 pub struct GlobalConfig {
     pub graphics_config: GraphicsConfig,
     pub input_config: InputConfig,
+    pub gameplay_config: GameplayConfig,
 }
 
 impl GlobalConfig {
@@ -35,6 +51,13 @@ impl GlobalConfig {
         toml::from_str(&content).expect("Failed to parse toml file")
     }
 
+    /// Same as [`GlobalConfig::load`] but returns an error instead of panicking.
+    /// Use this for files which are reloaded while the game is running.
+    pub fn try_load<T: for<'de> Deserialize<'de>>(path: &str) -> Result<T, String> {
+        let content = read_to_string(Path::new(path)).map_err(|e| format!("Failed to read '{path}': {e}"))?;
+        toml::from_str(&content).map_err(|e| format!("Failed to parse '{path}': {e}"))
+    }
+
     /// Creates a new `GlobalConfig` instance and loads all configuration files.
     ///
     ///
@@ -44,6 +67,7 @@ impl GlobalConfig {
         Self {
             graphics_config: Self::load("config/graphics.toml"),
             input_config: Self::load("config/input.toml"),
+            gameplay_config: Self::load(GAMEPLAY_CONFIG_PATH),
         }
     }
 
@@ -91,13 +115,13 @@ impl Default for GraphicsConfig {
 impl GraphicsConfig {
     pub fn get_window_width(&self) -> f32 {
         let (width, _) = parse_resolution(self.window_resolution.as_str())
-            .unwrap_or_else(|_| (1280.0, 720.0));
+            .unwrap_or((1280.0, 720.0));
         width
     }
 
     pub fn get_window_height(&self) -> f32 {
         let (_, height) = parse_resolution(self.window_resolution.as_str())
-            .unwrap_or_else(|_| (1280.0, 720.0));
+            .unwrap_or((1280.0, 720.0));
         height
     }
 }
@@ -113,10 +137,16 @@ pub struct InputConfig {
     pub inspector: String,
     pub system_info: String,
     pub gizmos_boxen: String,
+    pub reload_map: String,
     
     pub movement_left: String,
     pub movement_right: String,
     pub movement_jump: String,
+    pub movement_swim: String,
+    pub movement_grab: String,
+    pub movement_dash: String,
+
+    pub attack: String,
     
     pub interact: String,
 }
@@ -127,10 +157,16 @@ impl Default for InputConfig {
             inspector: String::from("F1"),
             system_info: String::from("F3"),
             gizmos_boxen: String::from("F9"),
+            reload_map: String::from("F5"),
             
             movement_left: String::from("A"),
             movement_right: String::from("D"),
             movement_jump: String::from("Space"),
+            movement_swim: String::from("W"),
+            movement_grab: String::from("F"),
+            movement_dash: String::from("ShiftLeft"),
+
+            attack: String::from("J"),
             
             interact: String::from("E")
         }
@@ -139,31 +175,306 @@ impl Default for InputConfig {
 
 impl InputConfig {
     pub fn get_inspector_key(&self) -> KeyCode {
-        convert(self.inspector.as_str()).unwrap_or_else(|| KeyCode::F1)
+        convert(self.inspector.as_str()).unwrap_or(KeyCode::F1)
     }
 
     pub fn get_gizmo_box_key(&self) -> KeyCode {
-        convert(self.gizmos_boxen.as_str()).unwrap_or_else(|| KeyCode::F9)
+        convert(self.gizmos_boxen.as_str()).unwrap_or(KeyCode::F9)
+    }
+
+    pub fn get_reload_map_key(&self) -> KeyCode {
+        convert(self.reload_map.as_str()).unwrap_or(KeyCode::F5)
     }
 
     pub fn get_move_left_key(&self) -> KeyCode {
-        convert(self.movement_left.as_str()).unwrap_or_else(|| KeyCode::KeyA)
+        convert(self.movement_left.as_str()).unwrap_or(KeyCode::KeyA)
     }
 
     pub fn get_move_right_key(&self) -> KeyCode {
-        convert(self.movement_right.as_str()).unwrap_or_else(|| KeyCode::KeyD)
+        convert(self.movement_right.as_str()).unwrap_or(KeyCode::KeyD)
     }
 
     pub fn get_jump_key(&self) -> KeyCode {
-        convert(self.movement_jump.as_str()).unwrap_or_else(|| KeyCode::Space)
+        convert(self.movement_jump.as_str()).unwrap_or(KeyCode::Space)
+    }
+
+    pub fn get_swim_key(&self) -> KeyCode {
+        convert(self.movement_swim.as_str()).unwrap_or(KeyCode::KeyW)
+    }
+
+    pub fn get_grab_key(&self) -> KeyCode {
+        convert(self.movement_grab.as_str()).unwrap_or(KeyCode::KeyF)
+    }
+
+    pub fn get_dash_key(&self) -> KeyCode {
+        convert(self.movement_dash.as_str()).unwrap_or(KeyCode::ShiftLeft)
+    }
+
+    pub fn get_attack_key(&self) -> KeyCode {
+        convert(self.attack.as_str()).unwrap_or(KeyCode::KeyJ)
     }
 
     pub fn get_interact_key(&self) -> KeyCode {
-        convert(self.interact.as_str()).unwrap_or_else(|| KeyCode::KeyE)
+        convert(self.interact.as_str()).unwrap_or(KeyCode::KeyE)
     }
     
 }
 
+// =================================================================================================
+//
+//                                            Gameplay
+//
+// =================================================================================================
+
+#[derive(Resource, Deserialize, Serialize, Clone, Debug, Default)]
+pub struct GameplayConfig {
+    #[serde(default)]
+    pub player: PlayerConfig,
+    #[serde(default)]
+    pub enemy: EnemyConfig,
+}
+
+/// Tuning values for the player movement. Every value can be overridden per map
+/// with a Tiled map property named `player.<field>` e.g. `player.gravity`.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct PlayerConfig {
+    pub gravity: f32,
+    pub max_fall_speed: f32,
+    pub speed: f32,
+    pub ground_acceleration: f32,
+    pub ground_deceleration: f32,
+    pub ground_turn_around: f32,
+    pub air_acceleration: f32,
+    pub air_deceleration: f32,
+    pub air_turn_around: f32,
+    pub jump_force: f32,
+    pub jump_time: f32,
+    /// Jumps the player can do while airborne once the double jump is unlocked.
+    pub air_jumps: u32,
+    /// Multiplier of the jump force for jumps while airborne.
+    pub air_jump_scale: f32,
+
+    pub land_time: f32,
+    pub hard_land_time: f32,
+    /// Impact speed from which the longer `hard_land_time` is used.
+    pub hard_landing_speed: f32,
+    /// Impact speed from which the player takes fall damage.
+    pub fall_damage_threshold: f32,
+    /// Damage per pixel per second above the `fall_damage_threshold`.
+    pub fall_damage_scale: f32,
+    pub hurt_time: f32,
+
+    /// Duration of one frame of the attack animation, the hitbox timing follows it.
+    pub attack_frame_duration: f32,
+    /// Freeze time of attacker and target when a hit lands.
+    pub attack_hit_stop: f32,
+    /// Horizontal speed the target is pushed away with.
+    pub attack_knockback: f32,
+
+    /// Horizontal speed during a dash, gravity is ignored while dashing.
+    pub dash_speed: f32,
+    pub dash_time: f32,
+    /// Time after a dash started until the next dash is possible.
+    pub dash_cooldown: f32,
+    /// Horizontal speed the player is pushed away from the wall on a wall jump.
+    pub wall_jump_push: f32,
+
+    pub water_gravity_scale: f32,
+    pub water_speed_scale: f32,
+    /// Upward acceleration while the player is submerged.
+    pub buoyancy: f32,
+    pub swim_up_speed: f32,
+    pub max_sink_speed: f32,
+    /// Breath refill per second while the head is above water.
+    pub breath_regen: f32,
+    /// Damage per second while the player has no breath left.
+    pub drown_damage: f32,
+
+    /// Max distance between the player hands and a rope segment to grab it.
+    pub grab_radius: f32,
+    /// Horizontal force the player applies to a grabbed rope.
+    pub swing_force: f32,
+    /// Multiplier of the jump force when the player jumps off a rope.
+    pub rope_jump_scale: f32,
+    /// Part of the player weight which pulls a grabbed rope down.
+    pub rope_weight_scale: f32,
+
+    /// Mass of the player which is used to push dynamic props.
+    pub mass: f32,
+    pub controller_offset: f32,
+    pub snap_to_ground: f32,
+    pub autostep_max_height: f32,
+    pub autostep_min_width: f32,
+    /// Angle in degrees.
+    pub max_slope_climb_angle: f32,
+    /// Angle in degrees.
+    pub min_slope_slide_angle: f32,
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            gravity: 300.0,
+            max_fall_speed: 1200.0,
+            speed: 200.0,
+            ground_acceleration: 1600.0,
+            ground_deceleration: 2000.0,
+            ground_turn_around: 3200.0,
+            air_acceleration: 900.0,
+            air_deceleration: 400.0,
+            air_turn_around: 1400.0,
+            jump_force: 250.0,
+            jump_time: 0.3,
+            air_jumps: 1,
+            air_jump_scale: 0.85,
+
+            land_time: 0.1,
+            hard_land_time: 0.35,
+            hard_landing_speed: 400.0,
+            fall_damage_threshold: 550.0,
+            fall_damage_scale: 0.25,
+            hurt_time: 0.4,
+
+            attack_frame_duration: 0.08,
+            attack_hit_stop: 0.08,
+            attack_knockback: 120.0,
+
+            dash_speed: 420.0,
+            dash_time: 0.15,
+            dash_cooldown: 0.6,
+            wall_jump_push: 180.0,
+
+            water_gravity_scale: 0.3,
+            water_speed_scale: 0.55,
+            buoyancy: 160.0,
+            swim_up_speed: 110.0,
+            max_sink_speed: 60.0,
+            breath_regen: 4.0,
+            drown_damage: 10.0,
+
+            grab_radius: 12.0,
+            swing_force: 40.0,
+            rope_jump_scale: 0.6,
+            rope_weight_scale: 0.1,
+
+            mass: 3.0,
+            controller_offset: 0.02,
+            snap_to_ground: 4.0,
+            autostep_max_height: 6.0,
+            autostep_min_width: 8.0,
+            max_slope_climb_angle: 55.0,
+            min_slope_slide_angle: 65.0,
+        }
+    }
+}
+
+impl PlayerConfig {
+
+    /// Returns a copy of this config with all `player.<field>` properties of the map applied.
+    /// The fields are matched through serde, so every field of the config can be overridden.
+    /// Unknown keys and non-numeric values are ignored with a warning.
+    pub fn with_map_overrides(&self, properties: &tiled::Properties) -> Self {
+        let Ok(toml::Value::Table(mut table)) = toml::Value::try_from(self) else {
+            return self.clone();
+        };
+
+        for (key, value) in properties.iter() {
+            let Some(field) = key.strip_prefix("player.") else { continue };
+            let Some(value) = value.as_number() else {
+                warn!("Map property '{}' is not a number", key);
+                continue;
+            };
+
+            match table.get_mut(field) {
+                Some(toml::Value::Integer(current)) => *current = value.max(0.0) as i64,
+                Some(current) => *current = toml::Value::Float(value as f64),
+                None => warn!("Unknown player map property '{}'", field),
+            }
+        }
+
+        table.try_into().unwrap_or_else(|error| {
+            warn!("Could not apply the player map properties: {}", error);
+            self.clone()
+        })
+    }
+}
+
+/// Tuning values which are shared by all enemies, independent of the player tuning.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct EnemyConfig {
+    pub gravity: f32,
+    pub max_fall_speed: f32,
+    pub controller_offset: f32,
+    pub snap_to_ground: f32,
+
+    pub attack_frame_duration: f32,
+    pub attack_startup_frames: u32,
+    /// Distance the swing reaches in front of the body.
+    pub attack_reach: f32,
+    /// Half width of the attack hitbox, the height follows the enemy size.
+    pub attack_half_width: f32,
+}
+
+impl Default for EnemyConfig {
+    fn default() -> Self {
+        Self {
+            gravity: 300.0,
+            max_fall_speed: 1200.0,
+            controller_offset: 0.02,
+            snap_to_ground: 4.0,
+
+            attack_frame_duration: 0.12,
+            attack_startup_frames: 2,
+            attack_reach: 6.0,
+            attack_half_width: 8.0,
+        }
+    }
+}
+
+/// Remembers the last modification time of the gameplay config so the file
+/// can be reloaded while the game is running.
+#[derive(Resource)]
+pub struct GameplayConfigWatcher {
+    pub timer: Timer,
+    pub modified: Option<SystemTime>,
+}
+
+impl Default for GameplayConfigWatcher {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+            modified: metadata(GAMEPLAY_CONFIG_PATH).and_then(|m| m.modified()).ok(),
+        }
+    }
+}
+
+#[coverage(off)]
+fn reload_gameplay_config(
+    time: Res<Time>,
+    mut watcher: ResMut<GameplayConfigWatcher>,
+    mut global_config: ResMut<GlobalConfig>,
+) {
+    if !watcher.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let Ok(modified) = metadata(GAMEPLAY_CONFIG_PATH).and_then(|m| m.modified()) else { return; };
+    if watcher.modified == Some(modified) {
+        return;
+    }
+    watcher.modified = Some(modified);
+
+    match GlobalConfig::try_load::<GameplayConfig>(GAMEPLAY_CONFIG_PATH) {
+        Ok(config) => {
+            global_config.gameplay_config = config;
+            info!("Reloaded {}", GAMEPLAY_CONFIG_PATH);
+        }
+        Err(error) => error!("{}", error),
+    }
+}
+
 // =================================================================================================
 //
 //                                         Internal Func
@@ -186,4 +497,30 @@ fn parse_resolution(s: &str) -> Result<(f32, f32), String> {
         return Err("Width / Height needs a positive number like > 0".into());
     }
     Ok((w, h))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use tiled::PropertyValue;
+    use super::*;
+
+    #[test]
+    fn test_player_map_overrides() {
+        let properties = tiled::Properties::from([
+            ("player.gravity".to_string(), PropertyValue::FloatValue(500.0)),
+            ("player.air_jumps".to_string(), PropertyValue::IntValue(2)),
+            ("player.rope_weight_scale".to_string(), PropertyValue::FloatValue(0.25)),
+            ("player.speed".to_string(), PropertyValue::StringValue("fast".to_string())),
+            ("player.unknown".to_string(), PropertyValue::FloatValue(1.0)),
+            ("music".to_string(), PropertyValue::StringValue("cave".to_string())),
+        ]);
+
+        let config = PlayerConfig::default().with_map_overrides(&properties);
+        assert_eq!(config, PlayerConfig {
+            gravity: 500.0,
+            air_jumps: 2,
+            rope_weight_scale: 0.25,
+            ..default()
+        });
+    }
+}
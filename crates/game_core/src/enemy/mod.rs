@@ -0,0 +1,141 @@
+#![coverage(off)]
+
+use bevy::prelude::*;
+
+pub struct EnemyModule;
+
+impl Plugin for EnemyModule {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.register_type::<Enemy>();
+        app.register_type::<EnemyAi>();
+        app.register_type::<EnemyPhysic>();
+        app.add_event::<EnemyAttack>();
+        app.add_event::<EnemyDefeated>();
+    }
+}
+
+/// Base component of every enemy. The values are read from the properties of the
+/// object in the `Enemies` object layer.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct Enemy {
+    pub kind: String,
+    pub health: i32,
+    pub damage: i32,
+    pub speed: f32,
+    pub chase_speed: f32,
+    /// Kind of the projectile a ranged enemy fires while chasing.
+    pub projectile: Option<String>,
+    /// Experience the player gets for defeating the enemy.
+    pub xp: u32,
+}
+
+impl Default for Enemy {
+    fn default() -> Self {
+        Self {
+            kind: String::from("walker"),
+            health: 30,
+            damage: 10,
+            speed: 40.0,
+            chase_speed: 90.0,
+            projectile: None,
+            xp: 10,
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EnemyAiState {
+    #[default]
+    Patrol,
+    Chase,
+    Attack,
+}
+
+/// Behaviour of an enemy. The enemy patrols around `home_x` and chases the player
+/// when it is inside of the `aggro_radius` and visible.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct EnemyAi {
+    pub state: EnemyAiState,
+    pub home_x: f32,
+    pub patrol_range: f32,
+    pub aggro_radius: f32,
+    /// `-1.0` for left and `1.0` for right.
+    pub direction: f32,
+    pub attack_cooldown: f32,
+    pub attack_timer: f32,
+    /// Path towards the player while chasing.
+    pub path: EnemyPath,
+}
+
+impl Default for EnemyAi {
+    fn default() -> Self {
+        Self {
+            state: EnemyAiState::Patrol,
+            home_x: 0.0,
+            patrol_range: 64.0,
+            aggro_radius: 120.0,
+            direction: 1.0,
+            attack_cooldown: 1.0,
+            attack_timer: 0.0,
+            path: EnemyPath::default(),
+        }
+    }
+}
+
+/// Planned path of a chasing enemy in tile coordinates. The enemy follows it step by
+/// step and only plans again on a timer or when it leaves the path or the goal moves.
+#[derive(Reflect, Debug, Clone, Default)]
+pub struct EnemyPath {
+    /// Remaining steps, the first one is the next cell to move to.
+    pub steps: Vec<EnemyPathStep>,
+    /// Cell on the path the enemy reached last.
+    pub start: Option<IVec2>,
+    pub goal: Option<IVec2>,
+    /// Seconds until the path is planned again.
+    pub replan_timer: f32,
+}
+
+impl EnemyPath {
+
+    /// Forces a new plan on the next step, e.g. after the navigation graph changed.
+    pub fn invalidate(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct EnemyPathStep {
+    pub cell: IVec2,
+    /// The cell is reached by dropping down from an edge.
+    pub drop: bool,
+}
+
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct EnemyPhysic {
+    pub half_size: Vec2,
+    pub velocity: Vec2,
+    pub grounded: bool,
+}
+
+/// Send when an enemy starts an attack against its target on contact. The damage
+/// is dealt by the hitbox of the attack.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct EnemyAttack {
+    pub enemy: Entity,
+    pub target: Entity,
+    pub damage: i32,
+}
+
+/// Send when the health of an enemy dropped to zero, right before it is despawned.
+#[derive(Event, Debug, Clone)]
+pub struct EnemyDefeated {
+    pub enemy: Entity,
+    pub kind: String,
+    pub position: Vec2,
+    pub xp: u32,
+}
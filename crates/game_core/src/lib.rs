@@ -9,10 +9,17 @@ pub mod player;
 pub mod tiled;
 pub mod world;
 pub mod animation;
+pub mod enemy;
+pub mod combat;
+pub mod stats;
 
 use bevy::prelude::*;
 use crate::animation::AnimationModule;
+use crate::combat::CombatModule;
+use crate::config::ConfigModule;
+use crate::enemy::EnemyModule;
 use crate::player::PlayerModule;
+use crate::stats::StatsModule;
 use crate::tiled::TiledModule;
 
 /// Core of all game relevant resources and structures. This Plugin initializes resources
@@ -24,7 +31,7 @@ impl Plugin for GameCorePlugin {
 
     #[coverage(off)]
     fn build(&self, app: &mut App) {
-        app.add_plugins((PlayerModule, AnimationModule, TiledModule));
+        app.add_plugins((ConfigModule, PlayerModule, EnemyModule, CombatModule, StatsModule, AnimationModule, TiledModule));
     }
 
 }
\ No newline at end of file
@@ -1,8 +1,13 @@
 #![coverage(off)]
 
-use bevy::prelude::*;
+pub mod progression;
 
-pub const GRAVITY : f32 = 300.0;
+use bevy::prelude::*;
+use crate::config::{GlobalConfig, PlayerConfig};
+use crate::player::progression::{load_progression_config, AbilityUnlocked, GainExperience, LevelUp, PlayerProgression, ProgressionConfig};
+use crate::tiled::LevelData;
+use crate::world::surface::SurfaceMaterial;
+use crate::world::water::{WaterRegions, WaterSplash};
 
 pub struct PlayerModule;
 
@@ -11,41 +16,97 @@ impl Plugin for PlayerModule {
     #[coverage(off)]
     fn build(&self, app: &mut App) {
         app.init_resource::<PlayerState>();
+        app.init_resource::<PlayerTuning>();
         app.register_type::<Player>();
+        app.add_event::<PlayerMotionChanged>();
+        app.add_event::<Landed>();
+        app.add_event::<AirJumped>();
+        app.init_resource::<WaterRegions>();
+        app.add_event::<WaterSplash>();
+        app.init_resource::<ProgressionConfig>();
+        app.add_event::<GainExperience>();
+        app.add_event::<LevelUp>();
+        app.add_event::<AbilityUnlocked>();
+        app.add_systems(Startup, load_progression_config);
+        app.add_systems(PreUpdate, refresh_player_tuning.run_if(
+            resource_exists::<GlobalConfig>.and(resource_changed::<GlobalConfig>.or(resource_changed::<LevelData>))
+        ));
     }
 }
 
-#[derive(Component, Reflect, Debug, Clone)]
+#[derive(Component, Reflect, Debug, Clone, Default)]
 #[reflect(Component)]
 pub struct Player {
     pub physic: PlayerPhysic,
     pub body: PlayerBody,
     pub stats: PlayerStats,
-    pub base_stats: PlayerBaseStats
+    pub base_stats: PlayerBaseStats,
+    pub motion: PlayerMotion,
+    pub progression: PlayerProgression
+}
+
+impl Player {
+
+    /// The player is dead as soon as the health is gone, before the motion state follows.
+    pub fn is_dead(&self) -> bool {
+        self.motion.state == PlayerMotionState::Dead || self.stats.health <= 0
+    }
 }
 
+/// Stats without any [`crate::stats::StatModifier`]. Speed and jump force follow the
+/// [`PlayerTuning`], the other values come from the properties of the player object.
 #[derive(Component, Reflect, Debug, Clone)]
 pub struct PlayerBaseStats {
+    /// The maximum health.
     pub health: i32,
+    /// Seconds the player can stay under water.
+    pub breath: f32,
+    /// Base damage of the melee attack.
+    pub damage: i32,
+    /// Flat reduction of the damage of every hit.
+    pub defense: i32,
+    pub speed: f32,
+    pub jump_force: f32,
 }
 
 impl Default for PlayerBaseStats {
     fn default() -> Self {
         Self {
-            health: 100
+            health: 100,
+            breath: 10.0,
+            damage: 10,
+            defense: 0,
+            speed: 200.0,
+            jump_force: 250.0
         }
     }
 }
 
+/// Current stats of the player, the base stats with all active modifiers applied.
 #[derive(Component, Reflect, Debug, Clone)]
 pub struct PlayerStats {
-    pub health: i32
+    pub health: i32,
+    pub max_health: i32,
+    pub breath: f32,
+    /// Drowning damage which is not a whole health point yet, reset above water.
+    pub drown_damage: f32,
+    pub damage: i32,
+    pub defense: i32,
+    pub speed: f32,
+    pub jump_force: f32,
 }
 
 impl Default for PlayerStats {
     fn default() -> Self {
         Self {
-            health: 100
+            health: 100,
+            max_health: 100,
+            breath: 10.0,
+            drown_damage: 0.0,
+            damage: 10,
+            defense: 0,
+            speed: 200.0,
+            jump_force: 250.0
         }
     }
 }
@@ -54,44 +115,219 @@ impl Default for PlayerStats {
 pub struct PlayerBody {
     pub half_size: Vec2,
     pub horizontal: i32,
+    pub swim_up: bool,
 }
 
 #[derive(Component, Reflect, Debug, Clone)]
 pub struct PlayerPhysic {
     pub speed: f32,
+    pub ground_acceleration: f32,
+    pub ground_deceleration: f32,
+    pub ground_turn_around: f32,
+    pub air_acceleration: f32,
+    pub air_deceleration: f32,
+    pub air_turn_around: f32,
     pub jump_force: f32,
     pub velocity : Vec2,
     pub grounded : bool,
     pub released_jump : bool,
     pub jump_time : f32,
     pub jump_timer : f32,
+    /// Jumps while airborne which are allowed between two landings.
+    pub max_air_jumps : u32,
+    /// Remaining jumps while airborne, refilled on landing.
+    pub air_jumps : u32,
+    /// An airborne jump was requested and is applied with the next physics step.
+    pub air_jump : bool,
+    /// The player is rising from an airborne jump.
+    pub air_jumping : bool,
+    /// Material of the ground below the player, the default material while airborne.
+    pub surface : SurfaceMaterial,
+    /// The body of the player is inside a water region.
+    pub in_water : bool,
+    /// The head of the player is inside a water region.
+    pub submerged : bool,
+    /// The rope segment the player is holding.
+    pub rope : Option<Entity>,
+    /// Remaining time of the current dash.
+    pub dash_timer : f32,
+    /// Remaining time until the next dash is possible.
+    pub dash_cooldown : f32,
+    pub dash_direction : f32,
+    /// Side of a wall the airborne player is pressed against, `-1` left, `1` right, `0` none.
+    pub wall : i32,
+    /// A wall jump was requested and is applied with the next physics step.
+    pub wall_jump : bool,
 }
 
 impl Default for PlayerPhysic {
     fn default() -> Self {
+        Self::from_config(&PlayerConfig::default())
+    }
+}
+
+impl PlayerPhysic {
+
+    /// Creates the physic values of a player from the given tuning.
+    pub fn from_config(config: &PlayerConfig) -> Self {
         Self {
-            speed: 200.0,
-            jump_force: 250.0,
+            speed: config.speed,
+            ground_acceleration: config.ground_acceleration,
+            ground_deceleration: config.ground_deceleration,
+            ground_turn_around: config.ground_turn_around,
+            air_acceleration: config.air_acceleration,
+            air_deceleration: config.air_deceleration,
+            air_turn_around: config.air_turn_around,
+            jump_force: config.jump_force,
             velocity: Vec2::new(0., -0.1),
             grounded: false,
             released_jump: false,
-            jump_time: 0.3,
-            jump_timer: 0.0
+            jump_time: config.jump_time,
+            jump_timer: 0.0,
+            max_air_jumps: config.air_jumps,
+            air_jumps: 0,
+            air_jump: false,
+            air_jumping: false,
+            surface: SurfaceMaterial::default(),
+            in_water: false,
+            submerged: false,
+            rope: None,
+            dash_timer: 0.0,
+            dash_cooldown: 0.0,
+            dash_direction: 0.0,
+            wall: 0,
+            wall_jump: false,
         }
     }
+
+    /// Applies the tuning values without touching the runtime state like velocity.
+    /// Speed and jump force are derived from the [`PlayerStats`] instead.
+    pub fn apply_config(&mut self, config: &PlayerConfig) {
+        self.ground_acceleration = config.ground_acceleration;
+        self.ground_deceleration = config.ground_deceleration;
+        self.ground_turn_around = config.ground_turn_around;
+        self.air_acceleration = config.air_acceleration;
+        self.air_deceleration = config.air_deceleration;
+        self.air_turn_around = config.air_turn_around;
+        self.jump_time = config.jump_time;
+        self.max_air_jumps = config.air_jumps;
+    }
 }
 
-impl Default for Player {
+/// All motion states the player can be in. The current state is evaluated
+/// in `FixedUpdate` from the physics result and decides which animation is played.
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PlayerMotionState {
+    #[default]
+    Idle,
+    Run,
+    Jump,
+    DoubleJump,
+    Fall,
+    Land,
+    Swim,
+    Swing,
+    Attack,
+    Hurt,
+    Dead
+}
+
+impl PlayerMotionState {
+
+    /// Returns the animation name which is registered at the player [`crate::animation::Animator`].
+    pub fn animation(&self) -> &'static str {
+        match self {
+            PlayerMotionState::Idle => "idle",
+            PlayerMotionState::Run => "run",
+            PlayerMotionState::Jump => "jump",
+            PlayerMotionState::DoubleJump => "double_jump",
+            PlayerMotionState::Fall => "fall",
+            PlayerMotionState::Land => "land",
+            PlayerMotionState::Swim => "swim",
+            PlayerMotionState::Swing => "swing",
+            PlayerMotionState::Attack => "attack",
+            PlayerMotionState::Hurt => "hurt",
+            PlayerMotionState::Dead => "dead",
+        }
+    }
+
+    /// Returns `true` if the state is an airborne state.
+    pub fn is_airborne(&self) -> bool {
+        matches!(self, PlayerMotionState::Jump | PlayerMotionState::DoubleJump | PlayerMotionState::Fall)
+    }
+}
+
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct PlayerMotion {
+    pub state: PlayerMotionState,
+    pub previous: PlayerMotionState,
+    pub time_in_state: f32,
+    pub land_time: f32,
+    pub hurt_timer: f32,
+}
+
+impl Default for PlayerMotion {
     fn default() -> Self {
         Self {
-            physic: PlayerPhysic::default(),
-            body: PlayerBody::default(),
-            stats: PlayerStats::default(),
-            base_stats: PlayerBaseStats::default()
+            state: PlayerMotionState::Idle,
+            previous: PlayerMotionState::Idle,
+            time_in_state: 0.0,
+            land_time: 0.1,
+            hurt_timer: 0.0
         }
     }
 }
 
+/// Send every time the [`PlayerMotionState`] of a player changes.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlayerMotionChanged {
+    pub entity: Entity,
+    pub from: PlayerMotionState,
+    pub to: PlayerMotionState,
+}
+
+/// The player tuning which is used by the movement systems. It is the `[player]`
+/// section of the gameplay config with the overrides of the current map applied.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct PlayerTuning(pub PlayerConfig);
+
+#[coverage(off)]
+fn refresh_player_tuning(
+    global_config: Res<GlobalConfig>,
+    level_data: Res<LevelData>,
+    mut tuning: ResMut<PlayerTuning>,
+) {
+    let config = &global_config.gameplay_config.player;
+    let next = match level_data.map.as_ref() {
+        Some(map) => config.with_map_overrides(&map.properties),
+        None => config.clone(),
+    };
+
+    if tuning.0 != next {
+        debug!("Player tuning updated: {:?}", next);
+        tuning.0 = next;
+    }
+}
+
+/// Send when a player touches the ground after being airborne. Use it as hook for
+/// camera shake, dust effects or fall damage.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Landed {
+    pub entity: Entity,
+    /// Downward speed in pixel per second right before the impact.
+    pub impact_speed: f32,
+}
+
+/// Send when the player jumps while airborne. Use it as hook for the jump
+/// effect and sound.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AirJumped {
+    pub entity: Entity,
+    pub position: Vec2,
+    /// Airborne jumps left until the next landing.
+    pub jumps_left: u32,
+}
+
 #[derive(Resource,Default)]
 pub struct PlayerState {
     pub spawned : bool,
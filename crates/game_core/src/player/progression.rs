@@ -0,0 +1,125 @@
+#![coverage(off)]
+
+use bevy::prelude::*;
+use serde::Deserialize;
+use crate::config::GlobalConfig;
+
+pub const PROGRESSION_CONFIG_PATH: &str = "config/progression.toml";
+
+/// Movement abilities which have to be unlocked before they can be used.
+#[derive(Reflect, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Ability {
+    DoubleJump,
+    Dash,
+    WallJump,
+}
+
+impl Ability {
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            "double_jump" => Some(Ability::DoubleJump),
+            "dash" => Some(Ability::Dash),
+            "wall_jump" => Some(Ability::WallJump),
+            _ => None,
+        }
+    }
+}
+
+/// Level, experience and unlocked abilities of the player.
+#[derive(Reflect, Debug, Clone)]
+pub struct PlayerProgression {
+    pub level: u32,
+    /// Total experience collected so far.
+    pub xp: u32,
+    pub abilities: Vec<Ability>,
+}
+
+impl Default for PlayerProgression {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            xp: 0,
+            abilities: Vec::new(),
+        }
+    }
+}
+
+impl PlayerProgression {
+
+    pub fn has(&self, ability: Ability) -> bool {
+        self.abilities.contains(&ability)
+    }
+
+    /// Unlocks the ability, returns `false` if it was already unlocked.
+    pub fn unlock(&mut self, ability: Ability) -> bool {
+        if self.has(ability) {
+            return false;
+        }
+        self.abilities.push(ability);
+        true
+    }
+}
+
+/// Requirement and reward of one level. The first entry is level 2.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct LevelStep {
+    /// Total experience which is needed to reach the level.
+    pub xp: u32,
+    /// Growth of the base stats.
+    pub health: i32,
+    pub damage: i32,
+    pub defense: i32,
+    pub unlock: Option<Ability>,
+}
+
+/// The level curve, loaded from `config/progression.toml`.
+#[derive(Resource, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ProgressionConfig {
+    pub levels: Vec<LevelStep>,
+}
+
+impl ProgressionConfig {
+
+    pub fn max_level(&self) -> u32 {
+        self.levels.len() as u32 + 1
+    }
+
+    /// Returns the step which leads to `level`, `None` for level 1 and above the curve.
+    pub fn step(&self, level: u32) -> Option<&LevelStep> {
+        self.levels.get(level.checked_sub(2)? as usize)
+    }
+}
+
+/// Request to give experience to the player, e.g. from a defeated enemy or a pickup.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct GainExperience {
+    pub entity: Entity,
+    pub amount: u32,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct LevelUp {
+    pub entity: Entity,
+    pub level: u32,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AbilityUnlocked {
+    pub entity: Entity,
+    pub ability: Ability,
+}
+
+#[coverage(off)]
+pub(crate) fn load_progression_config(mut config: ResMut<ProgressionConfig>) {
+    match GlobalConfig::try_load::<ProgressionConfig>(PROGRESSION_CONFIG_PATH) {
+        Ok(loaded) => {
+            info!("Loaded level curve with {} levels", loaded.max_level());
+            *config = loaded;
+        }
+        Err(error) => error!("{}", error),
+    }
+}
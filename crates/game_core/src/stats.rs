@@ -0,0 +1,143 @@
+#![coverage(off)]
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+pub struct StatsModule;
+
+impl Plugin for StatsModule {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.register_type::<StatusEffects>();
+        app.add_event::<ApplyStatusEffect>();
+        app.add_event::<StatChanged>();
+    }
+}
+
+/// All stats which can be changed by a [`StatModifier`].
+#[derive(Reflect, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum StatKind {
+    /// The maximum health.
+    Health,
+    Speed,
+    JumpForce,
+    Damage,
+    Defense,
+}
+
+/// How a modifier is combined with the base value. All additive values are summed
+/// up first, the result is multiplied with every multiplicative value.
+#[derive(Reflect, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ModifierOp {
+    Add,
+    Multiply,
+}
+
+#[derive(Reflect, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct StatModifier {
+    pub stat: StatKind,
+    pub op: ModifierOp,
+    pub value: f32,
+}
+
+/// A named effect like a buff, poison or slow. Applying an effect with the name of
+/// an active effect refreshes it instead of stacking it twice.
+#[derive(Reflect, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct StatusEffect {
+    pub name: String,
+    /// Seconds the effect lasts, `None` lasts until it is removed.
+    pub duration: Option<f32>,
+    pub damage_per_second: f32,
+    pub modifiers: Vec<StatModifier>,
+    #[serde(skip)]
+    pub remaining: f32,
+    /// Damage below one point which is carried over to the next tick.
+    #[serde(skip)]
+    pub pending_damage: f32,
+}
+
+impl StatusEffect {
+
+    pub fn new(name: &str, duration: Option<f32>) -> Self {
+        Self {
+            name: name.to_string(),
+            duration,
+            remaining: duration.unwrap_or_default(),
+            ..default()
+        }
+    }
+
+    pub fn with_modifier(mut self, stat: StatKind, op: ModifierOp, value: f32) -> Self {
+        self.modifiers.push(StatModifier { stat, op, value });
+        self
+    }
+
+    pub fn with_damage_per_second(mut self, damage_per_second: f32) -> Self {
+        self.damage_per_second = damage_per_second;
+        self
+    }
+
+    /// Multiplies the speed with `factor` for `duration` seconds.
+    pub fn slow(factor: f32, duration: f32) -> Self {
+        Self::new("slow", Some(duration)).with_modifier(StatKind::Speed, ModifierOp::Multiply, factor)
+    }
+
+    pub fn poison(damage_per_second: f32, duration: f32) -> Self {
+        Self::new("poison", Some(duration)).with_damage_per_second(damage_per_second)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.duration.is_some() && self.remaining <= 0.0
+    }
+}
+
+/// Active effects of an entity.
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct StatusEffects {
+    pub effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+
+    /// Adds the effect or refreshes the active effect with the same name.
+    pub fn apply(&mut self, mut effect: StatusEffect) {
+        effect.remaining = effect.duration.unwrap_or_default();
+        if let Some(active) = self.effects.iter_mut().find(|active| active.name == effect.name) {
+            effect.pending_damage = active.pending_damage;
+            *active = effect;
+        } else {
+            self.effects.push(effect);
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.effects.retain(|effect| effect.name != name);
+    }
+
+    pub fn modifiers(&self, stat: StatKind) -> impl Iterator<Item = &StatModifier> {
+        self.effects.iter()
+            .flat_map(|effect| effect.modifiers.iter())
+            .filter(move |modifier| modifier.stat == stat)
+    }
+}
+
+/// Request to apply a [`StatusEffect`] on the target.
+#[derive(Event, Debug, Clone)]
+pub struct ApplyStatusEffect {
+    pub target: Entity,
+    pub effect: StatusEffect,
+}
+
+/// Send when the value of a stat changed, e.g. by a modifier which was added or expired.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct StatChanged {
+    pub entity: Entity,
+    pub stat: StatKind,
+    pub from: f32,
+    pub to: f32,
+}
@@ -0,0 +1,369 @@
+#![coverage(off)]
+
+//! Conversion of the Tiled JSON formats into their XML counterparts. The `tiled` crate
+//! only parses XML, so JSON maps (`.tmj`), tilesets (`.tsj`) and templates (`.tj`) are
+//! rewritten to TMX, TSX and TX before they are handed over to it.
+
+use std::path::Path;
+use serde::de::Error as _;
+use serde_json::{Map, Value};
+
+/// Extensions of the Tiled JSON formats.
+pub const JSON_EXTENSIONS: [&str; 4] = ["tmj", "tsj", "tj", "json"];
+
+pub fn is_json_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| JSON_EXTENSIONS.contains(&extension))
+}
+
+/// Converts a JSON map, tileset or template into XML. The kind is taken from the `type`
+/// field of the document.
+pub fn to_xml(bytes: &[u8]) -> serde_json::Result<String> {
+    let document: Value = serde_json::from_slice(bytes)?;
+    let mut writer = XmlWriter::default();
+    match document.get("type").and_then(Value::as_str) {
+        Some("map") => writer.map(&document),
+        Some("tileset") => writer.tileset(&document, None),
+        Some("template") => writer.template(&document),
+        other => return Err(serde_json::Error::custom(format!("Unknown Tiled JSON type {other:?}"))),
+    }
+    Ok(writer.out)
+}
+
+#[derive(Default)]
+struct XmlWriter {
+    out: String,
+}
+
+impl XmlWriter {
+
+    fn map(&mut self, map: &Value) {
+        self.out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        self.open("map", map, &[
+            "version", "tiledversion", "class", "orientation", "renderorder", "width", "height",
+            "tilewidth", "tileheight", "infinite", "hexsidelength", "staggeraxis", "staggerindex",
+            "parallaxoriginx", "parallaxoriginy", "backgroundcolor", "nextlayerid", "nextobjectid",
+        ], false);
+        self.properties(map);
+
+        for tileset in array(map, "tilesets") {
+            if tileset.get("source").is_some() {
+                self.open("tileset", tileset, &["firstgid", "source"], true);
+            } else {
+                self.tileset(tileset, tileset.get("firstgid"));
+            }
+        }
+        self.layers(map);
+        self.close("map");
+    }
+
+    fn tileset(&mut self, tileset: &Value, first_gid: Option<&Value>) {
+        if first_gid.is_none() {
+            self.out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        }
+        self.open("tileset", tileset, &[
+            "firstgid", "version", "tiledversion", "name", "class", "tilewidth", "tileheight",
+            "spacing", "margin", "tilecount", "columns", "objectalignment", "tilerendersize", "fillmode",
+        ], false);
+
+        if let Some(offset) = tileset.get("tileoffset") {
+            self.open("tileoffset", offset, &["x", "y"], true);
+        }
+        self.image(tileset);
+        self.properties(tileset);
+
+        for tile in array(tileset, "tiles") {
+            self.open_with("tile", &[
+                ("id", tile.get("id")),
+                ("type", tile.get("type").or(tile.get("class"))),
+                ("probability", tile.get("probability")),
+            ], false);
+            self.properties(tile);
+            self.image(tile);
+            if let Some(group) = tile.get("objectgroup") {
+                self.object_group(group);
+            }
+            if let Some(frames) = tile.get("animation").and_then(Value::as_array) {
+                self.open_with("animation", &[], false);
+                for frame in frames {
+                    self.open("frame", frame, &["tileid", "duration"], true);
+                }
+                self.close("animation");
+            }
+            self.close("tile");
+        }
+        self.close("tileset");
+    }
+
+    fn template(&mut self, template: &Value) {
+        self.out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        self.open_with("template", &[], false);
+        if let Some(tileset) = template.get("tileset") {
+            self.open("tileset", tileset, &["firstgid", "source"], true);
+        }
+        if let Some(object) = template.get("object") {
+            self.object(object);
+        }
+        self.close("template");
+    }
+
+    fn layers(&mut self, parent: &Value) {
+        for layer in array(parent, "layers") {
+            match layer.get("type").and_then(Value::as_str) {
+                Some("tilelayer") => self.tile_layer(layer),
+                Some("objectgroup") => self.object_group(layer),
+                Some("imagelayer") => {
+                    self.open("imagelayer", layer, &[LAYER_ATTRIBUTES, &["repeatx", "repeaty"]].concat(), false);
+                    self.properties(layer);
+                    self.image(layer);
+                    self.close("imagelayer");
+                }
+                Some("group") => {
+                    self.open("group", layer, LAYER_ATTRIBUTES, false);
+                    self.properties(layer);
+                    self.layers(layer);
+                    self.close("group");
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn tile_layer(&mut self, layer: &Value) {
+        self.open("layer", layer, &[LAYER_ATTRIBUTES, &["width", "height"]].concat(), false);
+        self.properties(layer);
+
+        let encoding = layer.get("encoding").and_then(Value::as_str).unwrap_or("csv");
+        self.open_with("data", &[
+            ("encoding", Some(&Value::from(encoding))),
+            ("compression", layer.get("compression")),
+        ], false);
+        if let Some(chunks) = layer.get("chunks").and_then(Value::as_array) {
+            for chunk in chunks {
+                self.open("chunk", chunk, &["x", "y", "width", "height"], false);
+                self.tile_data(chunk.get("data"));
+                self.close("chunk");
+            }
+        } else {
+            self.tile_data(layer.get("data"));
+        }
+        self.close("data");
+        self.close("layer");
+    }
+
+    /// Writes the gids as CSV, base64 data is written as it is.
+    fn tile_data(&mut self, data: Option<&Value>) {
+        match data {
+            Some(Value::Array(gids)) => {
+                let csv: Vec<String> = gids.iter().map(Value::to_string).collect();
+                self.out.push_str(&csv.join(","));
+            }
+            Some(Value::String(encoded)) => self.out.push_str(encoded),
+            _ => {}
+        }
+    }
+
+    fn object_group(&mut self, group: &Value) {
+        self.open("objectgroup", group, &[LAYER_ATTRIBUTES, &["color", "draworder"]].concat(), false);
+        self.properties(group);
+        for object in array(group, "objects") {
+            self.object(object);
+        }
+        self.close("objectgroup");
+    }
+
+    fn object(&mut self, object: &Value) {
+        self.open_with("object", &[
+            ("id", object.get("id")),
+            ("template", object.get("template")),
+            ("name", object.get("name")),
+            ("type", object.get("type").or(object.get("class"))),
+            ("gid", object.get("gid")),
+            ("x", object.get("x")),
+            ("y", object.get("y")),
+            ("width", object.get("width")),
+            ("height", object.get("height")),
+            ("rotation", object.get("rotation")),
+            ("visible", object.get("visible")),
+        ], false);
+        self.properties(object);
+
+        if object.get("ellipse").and_then(Value::as_bool) == Some(true) {
+            self.open_with("ellipse", &[], true);
+        }
+        if object.get("point").and_then(Value::as_bool) == Some(true) {
+            self.open_with("point", &[], true);
+        }
+        for shape in ["polygon", "polyline"] {
+            if let Some(points) = object.get(shape).and_then(Value::as_array) {
+                let points: Vec<String> = points.iter()
+                    .map(|point| format!("{},{}", number(point.get("x")), number(point.get("y"))))
+                    .collect();
+                self.open_with(shape, &[("points", Some(&Value::from(points.join(" "))))], true);
+            }
+        }
+        if let Some(text) = object.get("text") {
+            self.open("text", text, &[
+                "fontfamily", "pixelsize", "wrap", "color", "bold", "italic", "underline",
+                "strikeout", "kerning", "halign", "valign",
+            ], false);
+            self.out.push_str(&escape(text.get("text").and_then(Value::as_str).unwrap_or_default()));
+            self.close("text");
+        }
+        self.close("object");
+    }
+
+    fn image(&mut self, parent: &Value) {
+        let Some(source) = parent.get("image") else { return };
+        let transparent = parent.get("transparentcolor")
+            .and_then(Value::as_str)
+            .map(|color| Value::from(color.trim_start_matches('#')));
+        self.open_with("image", &[
+            ("source", Some(source)),
+            ("width", parent.get("imagewidth")),
+            ("height", parent.get("imageheight")),
+            ("trans", transparent.as_ref()),
+        ], true);
+    }
+
+    fn properties(&mut self, parent: &Value) {
+        let properties = array(parent, "properties");
+        if properties.is_empty() {
+            return;
+        }
+
+        self.open_with("properties", &[], false);
+        for property in properties {
+            let kind = property.get("type").and_then(Value::as_str).unwrap_or("string");
+            if kind == "class" {
+                self.open("property", property, &["name", "type", "propertytype"], false);
+                if let Some(Value::Object(members)) = property.get("value") {
+                    self.class_members(members);
+                }
+                self.close("property");
+            } else {
+                self.open_with("property", &[
+                    ("name", property.get("name")),
+                    ("type", Some(&Value::from(kind))),
+                    ("value", property.get("value").map(property_value).as_ref()),
+                ], true);
+            }
+        }
+        self.close("properties");
+    }
+
+    /// Members of a class property only store their values, the types are taken from them.
+    fn class_members(&mut self, members: &Map<String, Value>) {
+        if members.is_empty() {
+            return;
+        }
+
+        self.open_with("properties", &[], false);
+        for (name, value) in members {
+            let kind = match value {
+                Value::Bool(_) => "bool",
+                Value::Number(number) if number.is_i64() || number.is_u64() => "int",
+                Value::Number(_) => "float",
+                Value::Object(_) => "class",
+                _ => "string",
+            };
+            let name = Value::from(name.as_str());
+            let kind_value = Value::from(kind);
+            if let Value::Object(nested) = value {
+                self.open_with("property", &[("name", Some(&name)), ("type", Some(&kind_value))], false);
+                self.class_members(nested);
+                self.close("property");
+            } else {
+                self.open_with("property", &[
+                    ("name", Some(&name)),
+                    ("type", Some(&kind_value)),
+                    ("value", Some(&property_value(value))),
+                ], true);
+            }
+        }
+        self.close("properties");
+    }
+
+    /// Opens an element with all attributes of `keys` which are present in the JSON object.
+    fn open(&mut self, tag: &str, source: &Value, keys: &[&str], empty: bool) {
+        let attributes: Vec<(&str, Option<&Value>)> = keys.iter().map(|key| (*key, source.get(*key))).collect();
+        self.open_with(tag, &attributes, empty);
+    }
+
+    fn open_with(&mut self, tag: &str, attributes: &[(&str, Option<&Value>)], empty: bool) {
+        self.out.push('<');
+        self.out.push_str(tag);
+        for (name, value) in attributes {
+            let Some(value) = value else { continue };
+            let value = match value {
+                Value::Bool(flag) => if *flag { "1".to_string() } else { "0".to_string() },
+                Value::String(text) => text.clone(),
+                Value::Null | Value::Array(_) | Value::Object(_) => continue,
+                other => other.to_string(),
+            };
+            self.out.push_str(&format!(" {name}=\"{}\"", escape(&value)));
+        }
+        self.out.push_str(if empty { "/>\n" } else { ">\n" });
+    }
+
+    fn close(&mut self, tag: &str) {
+        self.out.push_str(&format!("</{tag}>\n"));
+    }
+}
+
+/// Attributes which are shared by all layer types.
+const LAYER_ATTRIBUTES: &[&str] = &[
+    "id", "name", "class", "opacity", "visible", "tintcolor", "offsetx", "offsety", "parallaxx", "parallaxy",
+];
+
+fn array<'a>(parent: &'a Value, key: &str) -> &'a [Value] {
+    parent.get(key).and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default()
+}
+
+fn number(value: Option<&Value>) -> String {
+    value.map(Value::to_string).unwrap_or_else(|| "0".to_string())
+}
+
+/// Property values keep `true` and `false`, other attributes use `1` and `0` for booleans.
+fn property_value(value: &Value) -> Value {
+    match value {
+        Value::Bool(flag) => Value::from(flag.to_string()),
+        other => other.clone(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' => escaped.push_str("&#9;"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_tileset_and_template_are_converted() {
+        let tileset = to_xml(br#"{"type":"tileset","name":"a & b","tilewidth":8,"tileheight":8,"tilecount":1,"columns":1,"image":"x.png","imagewidth":8,"imageheight":8}"#).unwrap();
+        assert!(tileset.contains(r#"<tileset name="a &amp; b" tilewidth="8" tileheight="8" tilecount="1" columns="1">"#));
+        assert!(tileset.contains(r#"<image source="x.png" width="8" height="8"/>"#));
+
+        let template = to_xml(br#"{"type":"template","object":{"name":"Spawn","visible":false,"point":true}}"#).unwrap();
+        assert!(template.contains(r#"<object name="Spawn" visible="0">"#));
+        assert!(template.contains("<point/>"));
+
+        assert!(to_xml(br#"{"type":"world"}"#).is_err());
+    }
+}
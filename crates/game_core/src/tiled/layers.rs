@@ -0,0 +1,151 @@
+#![coverage(off)]
+
+//! Runtime control of the spawned map layers, e.g. to reveal secret areas by fading out
+//! the layer in front of them. Layers are addressed by their name or by their path
+//! through the group layers like `Deco/Front`.
+
+use bevy::prelude::*;
+use bevy_ecs_tilemap::tiles::TileColor;
+use crate::tiled::{TiledLayer, TiledLayersStorage};
+
+/// Fades a layer and all layers nested inside of it to `opacity` within `duration` seconds.
+#[derive(Event, Debug, Clone)]
+pub struct FadeLayer {
+    pub name: String,
+    pub opacity: f32,
+    pub duration: f32,
+}
+
+/// Shows or hides a layer and all layers nested inside of it.
+#[derive(Event, Debug, Clone)]
+pub struct SetLayerVisible {
+    pub name: String,
+    pub visible: bool,
+}
+
+/// Runtime opacity of a layer on top of its authored opacity.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct LayerFade {
+    pub opacity: f32,
+    pub target: f32,
+    /// Opacity change per second.
+    pub speed: f32,
+}
+
+impl Default for LayerFade {
+    fn default() -> Self {
+        Self { opacity: 1.0, target: 1.0, speed: 0.0 }
+    }
+}
+
+impl LayerFade {
+
+    pub fn fade_to(&mut self, target: f32, duration: f32) {
+        self.target = target.clamp(0.0, 1.0);
+        self.speed = if duration > 0.0 { (self.target - self.opacity).abs() / duration } else { f32::INFINITY };
+    }
+
+    /// Moves the opacity towards the target and returns `true` if it changed.
+    pub fn advance(&mut self, delta: f32) -> bool {
+        if self.opacity == self.target {
+            return false;
+        }
+        if self.speed.is_infinite() {
+            self.opacity = self.target;
+        } else {
+            let step = self.speed * delta;
+            self.opacity += (self.target - self.opacity).clamp(-step, step);
+        }
+        true
+    }
+}
+
+#[coverage(off)]
+pub(crate) fn handle_layer_requests(
+    mut commands: Commands,
+    mut fades: EventReader<FadeLayer>,
+    mut visibility_requests: EventReader<SetLayerVisible>,
+    storage: Query<&TiledLayersStorage>,
+    mut layers: Query<(Entity, &TiledLayer, Option<&mut LayerFade>, &mut Visibility)>,
+) {
+    let Ok(storage) = storage.single() else { return };
+
+    for request in fades.read() {
+        let ids: Vec<u32> = layers.iter().filter(|(_, layer, ..)| layer.matches(&request.name)).map(|(_, layer, ..)| layer.id).collect();
+        if ids.is_empty() {
+            warn!("No layer {} to fade", request.name);
+        }
+        for entity in ids.into_iter().flat_map(|id| storage.with_descendants(id)) {
+            let Ok((entity, _, fade, _)) = layers.get_mut(entity) else { continue };
+            match fade {
+                Some(mut fade) => fade.fade_to(request.opacity, request.duration),
+                None => {
+                    let mut fade = LayerFade::default();
+                    fade.fade_to(request.opacity, request.duration);
+                    commands.entity(entity).insert(fade);
+                }
+            }
+        }
+    }
+
+    for request in visibility_requests.read() {
+        for (_, layer, _, mut visibility) in layers.iter_mut() {
+            if layer.matches(&request.name) {
+                *visibility = if request.visible { Visibility::Inherited } else { Visibility::Hidden };
+            }
+        }
+    }
+}
+
+/// Applies the fade to the tiles and sprites of a layer, nested layers apply their own.
+#[coverage(off)]
+pub(crate) fn fade_layers(
+    time: Res<Time>,
+    mut layers: Query<(Entity, &TiledLayer, &mut LayerFade)>,
+    children: Query<&Children>,
+    nested_layers: Query<(), With<TiledLayer>>,
+    mut tile_colors: Query<&mut TileColor>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    for (layer_entity, layer, mut fade) in layers.iter_mut() {
+        if !fade.advance(time.delta_secs()) {
+            continue;
+        }
+
+        let color = layer.color();
+        let color = color.with_alpha(color.alpha() * fade.opacity);
+        let mut pending = vec![layer_entity];
+        while let Some(entity) = pending.pop() {
+            if let Ok(mut tile_color) = tile_colors.get_mut(entity) {
+                tile_color.0 = color;
+            }
+            if let Ok(mut sprite) = sprites.get_mut(entity) {
+                sprite.color = color;
+            }
+            let Ok(entity_children) = children.get(entity) else { continue };
+            pending.extend(entity_children.iter().filter(|child| !nested_layers.contains(*child)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layer_fade_moves_towards_target() {
+        let mut fade = LayerFade::default();
+        assert!(!fade.advance(1.0));
+
+        fade.fade_to(0.0, 2.0);
+        assert!(fade.advance(1.0));
+        assert_eq!(fade.opacity, 0.5);
+        assert!(fade.advance(5.0));
+        assert_eq!(fade.opacity, 0.0);
+        assert!(!fade.advance(1.0));
+
+        fade.fade_to(1.0, 0.0);
+        assert!(fade.advance(0.0));
+        assert_eq!(fade.opacity, 1.0);
+    }
+}
@@ -2,24 +2,32 @@
 
 pub mod properties;
 pub mod objects;
+pub mod json;
+pub mod layers;
 
 use std::collections::HashMap;
-use std::io::{Cursor, Error, ErrorKind};
-use std::path::Path;
+use std::fmt::{Display, Formatter};
+use std::io::{Cursor, Error};
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
-use bevy::asset::{AssetLoader, LoadContext};
+use bevy::asset::{AssetLoader, LoadContext, RenderAssetUsages};
 use bevy::asset::io::Reader;
 use bevy::ecs::system::SystemId;
 use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 use bevy::sprite::Anchor;
+use bevy::tasks::block_on;
 use bevy_ecs_tilemap::anchor::TilemapAnchor;
 use bevy_ecs_tilemap::map::TilemapId;
 use bevy_ecs_tilemap::prelude::*;
 use bevy_ecs_tilemap::TilemapBundle;
 use bevy_ecs_tilemap::tiles::TileTextureIndex;
 use thiserror::Error;
-use tiled::{DefaultResourceCache, ObjectData};
-use crate::tiled::objects::{DoorEntered, DoorOverlap};
+use tiled::{ChunkData, DefaultResourceCache, LayerTile, ObjectData, TileLayer};
+use crate::camera::CameraGame;
+use crate::tiled::layers::{FadeLayer, SetLayerVisible};
+use crate::tiled::objects::{DoorEntered, DoorOverlap, TiledObject};
+use crate::world::props::PressurePlateChanged;
 
 pub struct TiledModule;
 
@@ -28,20 +36,198 @@ impl Plugin for TiledModule {
     #[coverage(off)]
     fn build(&self, app: &mut App) {
         app.register_asset_loader(TiledLoader);
+        app.register_asset_loader(TiledJsonLoader);
         app.init_asset::<TiledMap>();
         app.init_resource::<LevelData>();
         app.init_resource::<ObjectLayers>();
         app.init_resource::<DoorOverlap>();
         app.add_event::<DoorEntered>();
-        app.add_systems(Update, process_maps);
+        app.add_event::<PressurePlateChanged>();
+        app.add_event::<FadeLayer>();
+        app.add_event::<SetLayerVisible>();
+        app.add_systems(Update, (reload_modified_maps, process_maps, animate_tiles).chain());
+        app.add_systems(Update, (layers::handle_layer_requests, layers::fade_layers).chain().after(process_maps));
+        app.add_systems(PostUpdate, update_parallax.before(TransformSystem::TransformPropagate));
     }
 }
 
 #[derive(Resource, Default)]
 pub struct LevelData {
     pub map: Option<tiled::Map>,
+    /// Tiles of the `Collision` layer inside the `bounds`, row by row from the top.
     pub collision_map: Vec<i32>,
+    /// Used area of the map in Tiled tile coordinates, the max corner is exclusive.
+    /// Infinite maps can start at negative coordinates.
+    pub bounds: IRect,
     pub image_layers: Vec<ImageLayerData>,
+    /// The map asset the data was created from.
+    pub source: Option<AssetId<TiledMap>>,
+    /// `true` if the data replaced an earlier version of the same map asset, `false`
+    /// for the first load and when a different map was loaded.
+    pub reloaded: bool,
+}
+
+impl LevelData {
+
+    /// Creates the level data of a map. The image layers are added while the layers
+    /// of the map are spawned.
+    pub fn from_map(map: &tiled::Map) -> Self {
+        let bounds = map_tile_bounds(map);
+        Self {
+            map: Some(map.clone()),
+            collision_map: build_collision_map(map, bounds),
+            bounds,
+            image_layers: Vec::new(),
+            source: None,
+            reloaded: false,
+        }
+    }
+}
+
+/// Returns all tiles of a finite or infinite tile layer with their Tiled tile coordinates.
+pub fn layer_tiles<'map>(tile_layer: &TileLayer<'map>) -> Vec<(IVec2, LayerTile<'map>)> {
+    let mut tiles = Vec::new();
+    match tile_layer {
+        TileLayer::Finite(layer) => {
+            for y in 0..layer.height() as i32 {
+                for x in 0..layer.width() as i32 {
+                    if let Some(tile) = layer.get_tile(x, y) {
+                        tiles.push((IVec2::new(x, y), tile));
+                    }
+                }
+            }
+        }
+        TileLayer::Infinite(layer) => {
+            for ((chunk_x, chunk_y), chunk) in layer.chunks() {
+                let origin = IVec2::new(chunk_x * ChunkData::WIDTH as i32, chunk_y * ChunkData::HEIGHT as i32);
+                for y in 0..ChunkData::HEIGHT as i32 {
+                    for x in 0..ChunkData::WIDTH as i32 {
+                        if let Some(tile) = chunk.get_tile(x, y) {
+                            tiles.push((origin + IVec2::new(x, y), tile));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    tiles
+}
+
+/// Returns the used area of the map in tile coordinates. Finite maps use their size,
+/// infinite maps the area of all placed tiles.
+pub fn map_tile_bounds(map: &tiled::Map) -> IRect {
+    let size = IRect::new(0, 0, map.width as i32, map.height as i32);
+    if !map.infinite() {
+        return size;
+    }
+
+    let mut bounds: Option<IRect> = None;
+    for layer in map.layers() {
+        let tiled::LayerType::Tiles(tile_layer) = layer.layer_type() else { continue };
+        for (cell, _) in layer_tiles(&tile_layer) {
+            let tile = IRect::from_corners(cell, cell + IVec2::ONE);
+            bounds = Some(bounds.map_or(tile, |bounds| bounds.union(tile)));
+        }
+    }
+    bounds.unwrap_or(size)
+}
+
+/// Creates the collision map of all tiles of the `Collision` layer inside the bounds.
+fn build_collision_map(map: &tiled::Map, bounds: IRect) -> Vec<i32> {
+    let mut collision_map = vec![0; (bounds.width() * bounds.height()) as usize];
+    for layer in map.layers() {
+        if layer.name != "Collision" { continue; }
+        let tiled::LayerType::Tiles(tile_layer) = layer.layer_type() else { continue };
+        for (cell, _) in layer_tiles(&tile_layer) {
+            let local = cell - bounds.min;
+            collision_map[(local.x + local.y * bounds.width()) as usize] = 1;
+        }
+    }
+    collision_map
+}
+
+/// Changes between two versions of a map, logged when a map is reloaded.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MapDiff {
+    pub resized: bool,
+    pub tilesets_changed: bool,
+    /// Paths of the added, removed and changed layers, group layers are not listed.
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl MapDiff {
+
+    pub fn is_empty(&self) -> bool {
+        !self.resized && !self.tilesets_changed && self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Display for MapDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+
+        let mut parts = Vec::new();
+        if self.resized {
+            parts.push("size changed".to_string());
+        }
+        if self.tilesets_changed {
+            parts.push("tilesets changed".to_string());
+        }
+        for (kind, layers) in [("added", &self.added), ("removed", &self.removed), ("changed", &self.changed)] {
+            if !layers.is_empty() {
+                parts.push(format!("{kind} {}", layers.join(", ")));
+            }
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+/// Compares the size, the tilesets and all layers of two maps.
+pub fn diff_maps(old: &tiled::Map, new: &tiled::Map) -> MapDiff {
+    let old_layers = leaf_layers(old);
+    let new_layers = leaf_layers(new);
+    let find = |layers: &[(String, tiled::LayerData)], path: &str| {
+        layers.iter().find(|(other, _)| other == path).map(|(_, data)| data.clone())
+    };
+
+    let mut diff = MapDiff {
+        resized: (old.width, old.height, old.tile_width, old.tile_height) != (new.width, new.height, new.tile_width, new.tile_height),
+        tilesets_changed: old.tilesets() != new.tilesets(),
+        ..default()
+    };
+    for (path, data) in &new_layers {
+        match find(&old_layers, path) {
+            None => diff.added.push(path.clone()),
+            Some(old_data) if old_data != *data => diff.changed.push(path.clone()),
+            _ => {}
+        }
+    }
+    diff.removed = old_layers.iter()
+        .filter(|(path, _)| find(&new_layers, path).is_none())
+        .map(|(path, _)| path.clone())
+        .collect();
+    diff
+}
+
+/// All layers except group layers by their path through the groups.
+fn leaf_layers(map: &tiled::Map) -> Vec<(String, tiled::LayerData)> {
+    fn collect<'map>(layers: impl Iterator<Item = tiled::Layer<'map>>, prefix: &str, out: &mut Vec<(String, tiled::LayerData)>) {
+        for layer in layers {
+            let path = if prefix.is_empty() { layer.name.clone() } else { format!("{prefix}/{}", layer.name) };
+            match layer.layer_type() {
+                tiled::LayerType::Group(group) => collect(group.layers(), &path, out),
+                _ => out.push((path, (*layer).clone())),
+            }
+        }
+    }
+
+    let mut layers = Vec::new();
+    collect(map.layers(), "", &mut layers);
+    layers
 }
 
 #[derive(Clone)]
@@ -77,35 +263,206 @@ impl ObjectLayers {
 #[derive(TypePath, Asset)]
 pub struct TiledMap {
     pub map: tiled::Map,
-    pub tilemap_textures: HashMap<usize, TilemapTexture>
+    pub tilemap_textures: HashMap<usize, TilemapTexture>,
+    /// Runtime atlases of the image collection tilesets by tileset index.
+    pub atlases: HashMap<usize, TilesetAtlas>,
+}
+
+impl TiledMap {
+
+    /// Returns the index of the tile inside the texture of its tileset, `None` if the
+    /// image of the tile could not be loaded.
+    pub fn texture_index(&self, tileset_index: usize, tile_id: u32) -> Option<u32> {
+        match self.atlases.get(&tileset_index) {
+            Some(atlas) => atlas.indices.get(&tile_id).copied(),
+            None => Some(tile_id),
+        }
+    }
+
+    /// Animation of the tile as authored in the tileset, `None` for static tiles.
+    pub fn tile_animation(&self, tileset_index: usize, tile_id: u32) -> Option<TiledTileAnimation> {
+        let tileset = self.map.tilesets().get(tileset_index)?;
+        let frames: Vec<TiledAnimationFrame> = tileset.get_tile(tile_id)?.animation.as_ref()?
+            .iter()
+            .filter_map(|frame| Some(TiledAnimationFrame {
+                texture_index: self.texture_index(tileset_index, frame.tile_id)?,
+                duration: frame.duration as f32 / 1000.0,
+            }))
+            .collect();
+
+        // Frames without any duration would never advance
+        if frames.iter().all(|frame| frame.duration <= 0.0) {
+            return None;
+        }
+        Some(TiledTileAnimation { frames, frame: 0, elapsed: 0.0 })
+    }
+
+    /// Size of one tile inside the texture of the tileset.
+    pub fn tile_size(&self, tileset_index: usize) -> Option<TilemapTileSize> {
+        if let Some(atlas) = self.atlases.get(&tileset_index) {
+            return Some(TilemapTileSize { x: atlas.cell_size.x as f32, y: atlas.cell_size.y as f32 });
+        }
+        let tileset = self.map.tilesets().get(tileset_index)?;
+        Some(TilemapTileSize { x: tileset.tile_width as f32, y: tileset.tile_height as f32 })
+    }
+}
+
+/// Moves a layer with a fraction of the [`CameraGame`] movement, Tiled's `parallaxx` and
+/// `parallaxy` factors. The offset is zero while the camera is centered on the origin.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Parallax {
+    pub factor: Vec2,
+    /// Parallax origin in world coordinates, the top left corner of the map like in Tiled.
+    pub origin: Vec2,
+    /// Translation of the layer without any parallax offset.
+    pub base: Vec2,
+    /// Axes on which the layer image repeats, the offset wraps around the image `size`.
+    pub repeat: BVec2,
+    pub size: Vec2,
+}
+
+impl Parallax {
+
+    pub fn offset(&self, camera: Vec2) -> Vec2 {
+        let offset = (camera - self.origin) * (Vec2::ONE - self.factor);
+        let wrap = |offset: f32, repeat: bool, size: f32| {
+            if repeat && size > 0.0 { offset.rem_euclid(size) } else { offset }
+        };
+        Vec2::new(
+            wrap(offset.x, self.repeat.x, self.size.x),
+            wrap(offset.y, self.repeat.y, self.size.y),
+        )
+    }
+}
+
+#[coverage(off)]
+fn update_parallax(
+    camera: Query<&Transform, With<CameraGame>>,
+    mut layers: Query<(&Parallax, &mut Transform), Without<CameraGame>>,
+) {
+    let Ok(camera) = camera.single() else { return };
+    for (parallax, mut transform) in layers.iter_mut() {
+        let translation = parallax.base + parallax.offset(camera.translation.truncate());
+        transform.translation.x = translation.x;
+        transform.translation.y = translation.y;
+    }
 }
 
-struct BytesResourceReader {
+/// Frames of an animated tile, every frame is shown for its own duration.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct TiledTileAnimation {
+    pub frames: Vec<TiledAnimationFrame>,
+    pub frame: usize,
+    /// Time spent in the current frame.
+    pub elapsed: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TiledAnimationFrame {
+    pub texture_index: u32,
+    /// Duration in seconds.
+    pub duration: f32,
+}
+
+impl TiledTileAnimation {
+
+    pub fn texture_index(&self) -> u32 {
+        self.frames[self.frame].texture_index
+    }
+
+    /// Advances the animation by `delta` seconds and returns the new texture index if
+    /// the frame changed.
+    pub fn advance(&mut self, delta: f32) -> Option<u32> {
+        let total: f32 = self.frames.iter().map(|frame| frame.duration).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let start = self.frame;
+        self.elapsed += delta % total;
+        while self.elapsed >= self.frames[self.frame].duration {
+            self.elapsed -= self.frames[self.frame].duration;
+            self.frame = (self.frame + 1) % self.frames.len();
+        }
+        (self.frame != start).then(|| self.texture_index())
+    }
+}
+
+/// Texture atlas which is packed at load time from the single images of an image
+/// collection tileset. All cells have the size of the largest image and every image is
+/// placed in the bottom left corner of its cell, like Tiled aligns them on the grid.
+#[derive(Debug, Clone, Default)]
+pub struct TilesetAtlas {
+    pub cell_size: UVec2,
+    /// Atlas index of every tile id.
+    pub indices: HashMap<u32, u32>,
+}
+
+/// Resource reader for the `tiled` loader. The map itself is served from the already read
+/// bytes, external tilesets (`.tsx`) and templates (`.tx`) are read through the
+/// [`LoadContext`], which registers them as dependencies of the map. Changing one of them
+/// reloads the map. Tilesets and templates in the JSON formats are converted to XML.
+struct BytesResourceReader<'a, 'ctx> {
     bytes: Arc<[u8]>,
+    load_context: &'a mut LoadContext<'ctx>,
 }
 
-impl BytesResourceReader {
-    fn new(bytes: &[u8]) -> Self {
+impl<'a, 'ctx> BytesResourceReader<'a, 'ctx> {
+    fn new(bytes: &[u8], load_context: &'a mut LoadContext<'ctx>) -> Self {
         Self {
             bytes: Arc::from(bytes),
+            load_context,
         }
     }
 }
 
-impl tiled::ResourceReader for BytesResourceReader {
+impl tiled::ResourceReader for BytesResourceReader<'_, '_> {
     type Resource = Cursor<Arc<[u8]>>;
     type Error = std::io::Error;
 
-    fn read_from(&mut self, _path: &Path) -> Result<Self::Resource, Self::Error> {
-        Ok(Cursor::new(self.bytes.clone()))
+    fn read_from(&mut self, path: &Path) -> Result<Self::Resource, Self::Error> {
+        if path == self.load_context.path() {
+            return Ok(Cursor::new(self.bytes.clone()));
+        }
+
+        let path = normalize_asset_path(path);
+        let mut bytes = block_on(self.load_context.read_asset_bytes(path.clone())).map_err(|error| {
+            Error::other(format!("Could not read {}: {error}", path.display()))
+        })?;
+        if json::is_json_path(&path) {
+            bytes = json::to_xml(&bytes).map_err(|error| {
+                Error::other(format!("Could not convert {}: {error}", path.display()))
+            })?.into_bytes();
+        }
+        Ok(Cursor::new(Arc::from(bytes)))
     }
 
 }
 
+/// Resolves `.` and `..` components of a path which `tiled` joined relative to the
+/// referencing file, e.g. `maps/../tilesets/world.tsx` becomes `tilesets/world.tsx`.
+pub fn normalize_asset_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
 #[derive(Debug, Error)]
 pub enum TiledAssetLoaderError {
     #[error("Tiled asset loading error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("Tiled JSON error: {0}")]
+    Json(#[from] serde_json::Error),
 }
 
 pub struct TiledLoader;
@@ -123,48 +480,250 @@ impl AssetLoader for TiledLoader {
     ) -> Result<Self::Asset, Self::Error> {
         let mut bytes: Vec<u8> = Vec::new();
         reader.read_to_end(&mut bytes).await?;
+        load_tiled_map(&bytes, load_context).await
+    }
 
-        let mut loader: tiled::Loader<DefaultResourceCache, _> = tiled::Loader::with_cache_and_reader(
-            DefaultResourceCache::new(),
-            BytesResourceReader::new(&bytes)
-        );
+    fn extensions(&self) -> &[&str] {
+        &["tmx"]
+    }
+}
 
-        let map: tiled::Map = loader.load_tmx_map(load_context.path()).map_err(|error| {
-            Error::new(ErrorKind::Other, format!("Could not load TMX map: {error}"))
-        })?;
+/// Loads maps in the Tiled JSON format (`.tmj`). The map is converted to TMX and loaded
+/// like the maps of the [`TiledLoader`], so both produce the same [`TiledMap`].
+pub struct TiledJsonLoader;
 
-        let mut tilemap_textures = HashMap::new();
+impl AssetLoader for TiledJsonLoader {
+    type Asset = TiledMap;
+    type Settings = ();
+    type Error = TiledAssetLoaderError;
 
-        for(tileset_index, tileset) in map.tilesets().iter().enumerate() {
-            let tilemap_texture: TilemapTexture = match &tileset.image {
-                None => {
-                    warn!("Unsupported tileset type {}", tileset.name);
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes: Vec<u8> = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let tmx = json::to_xml(&bytes)?;
+        load_tiled_map(tmx.as_bytes(), load_context).await
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tmj"]
+    }
+}
+
+/// Parses the TMX bytes of the map at the path of the load context and loads the textures
+/// of all tilesets.
+async fn load_tiled_map(bytes: &[u8], load_context: &mut LoadContext<'_>) -> Result<TiledMap, TiledAssetLoaderError> {
+    let map_path = load_context.path().to_path_buf();
+    let mut loader: tiled::Loader<DefaultResourceCache, _> = tiled::Loader::with_cache_and_reader(
+        DefaultResourceCache::new(),
+        BytesResourceReader::new(bytes, load_context)
+    );
+
+    let map: tiled::Map = loader.load_tmx_map(&map_path).map_err(|error| {
+        Error::other(format!("Could not load TMX map: {error}"))
+    })?;
+
+    let mut tilemap_textures = HashMap::new();
+    let mut atlases = HashMap::new();
+
+    for(tileset_index, tileset) in map.tilesets().iter().enumerate() {
+        let tilemap_texture: TilemapTexture = match &tileset.image {
+            None => {
+                let Some((atlas_image, atlas)) = load_tileset_atlas(tileset, load_context).await else {
+                    warn!("Tileset {} has no loadable images", tileset.name);
                     continue;
-                },
-                Some(img) => {
-                    let texture_path: &str = img.source.to_str().unwrap();
-                    let texture: Handle<Image> = load_context.load(texture_path);
+                };
+                atlases.insert(tileset_index, atlas);
+                TilemapTexture::Single(load_context.add_labeled_asset(format!("atlas{tileset_index}"), atlas_image))
+            },
+            Some(img) => {
+                let texture: Handle<Image> = load_context.load(normalize_asset_path(&img.source));
 
-                    TilemapTexture::Single(texture.clone())
-                }
-            };
+                TilemapTexture::Single(texture.clone())
+            }
+        };
+
+        tilemap_textures.insert(tileset_index, tilemap_texture);
+    }
+
+    let asset_map: TiledMap = TiledMap {
+        map,
+        tilemap_textures,
+        atlases
+    };
+
+    debug!("Loaded TMX map: {}", load_context.path().display());
+    Ok(asset_map)
+}
+
+#[coverage(off)]
+fn animate_tiles(time: Res<Time>, mut tiles: Query<(&mut TiledTileAnimation, &mut TileTextureIndex)>) {
+    for (mut animation, mut texture_index) in tiles.iter_mut() {
+        if let Some(index) = animation.advance(time.delta_secs()) {
+            texture_index.0 = index;
+        }
+    }
+}
 
-            tilemap_textures.insert(tileset_index, tilemap_texture);
+/// Loads all images of an image collection tileset and packs them into one atlas.
+async fn load_tileset_atlas(tileset: &tiled::Tileset, load_context: &mut LoadContext<'_>) -> Option<(Image, TilesetAtlas)> {
+    let mut sources: Vec<(u32, PathBuf)> = tileset.tiles()
+        .filter_map(|(id, tile)| tile.image.as_ref().map(|image| (id, normalize_asset_path(&image.source))))
+        .collect();
+    sources.sort_by_key(|(id, _)| *id);
+
+    let mut images = Vec::new();
+    for (id, path) in sources {
+        match load_context.loader().immediate().load::<Image>(path.clone()).await {
+            Ok(image) => images.push((id, image.take())),
+            Err(error) => warn!("Could not load tile image {}: {error}", path.display()),
         }
+    }
+    pack_tileset_atlas(images)
+}
 
-        let asset_map: TiledMap = TiledMap {
-            map,
-            tilemap_textures
-        };
+/// Packs the tile images into a grid of equally sized cells, `None` without any image.
+pub fn pack_tileset_atlas(images: Vec<(u32, Image)>) -> Option<(Image, TilesetAtlas)> {
+    let format = TextureFormat::Rgba8UnormSrgb;
+    let images: Vec<(u32, Image)> = images.into_iter()
+        .filter_map(|(id, image)| {
+            let image = if image.texture_descriptor.format == format { Some(image) } else { image.convert(format) };
+            image.filter(|image| image.data.is_some()).map(|image| (id, image))
+        })
+        .collect();
+    if images.is_empty() {
+        return None;
+    }
 
-        debug!("Loaded TMX map: {}", load_context.path().display());
-        Ok(asset_map)
+    let cell_size = images.iter().fold(UVec2::ZERO, |size, (_, image)| size.max(image.size()));
+    let columns = (images.len() as f32).sqrt().ceil() as u32;
+    let rows = (images.len() as u32).div_ceil(columns);
+    let atlas_width = columns * cell_size.x;
+
+    let mut atlas_image = Image::new_fill(
+        Extent3d { width: atlas_width, height: rows * cell_size.y, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        format,
+        RenderAssetUsages::default(),
+    );
+    let mut atlas = TilesetAtlas { cell_size, indices: HashMap::new() };
+    let atlas_data = atlas_image.data.as_mut()?;
+
+    for (atlas_index, (id, image)) in images.iter().enumerate() {
+        let atlas_index = atlas_index as u32;
+        let cell = UVec2::new(atlas_index % columns, atlas_index / columns) * cell_size;
+        let size = image.size();
+        let data = image.data.as_ref()?;
+        let row_bytes = (size.x * 4) as usize;
+
+        for row in 0..size.y {
+            let target_y = cell.y + cell_size.y - size.y + row;
+            let target = ((target_y * atlas_width + cell.x) * 4) as usize;
+            let source = row as usize * row_bytes;
+            atlas_data[target..target + row_bytes].copy_from_slice(&data[source..source + row_bytes]);
+        }
+        atlas.indices.insert(*id, atlas_index);
     }
+
+    Some((atlas_image, atlas))
 }
 
+/// Entities of the spawned layers. Layers inside a group layer are children of the
+/// group entity, so hiding or moving a group applies to all of its layers.
 #[derive(Component, Default)]
 pub struct TiledLayersStorage {
+    /// Layer entities by Tiled layer id.
     pub storage: HashMap<u32, Entity>,
+    /// Ids of the direct child layers of every group layer.
+    pub children: HashMap<u32, Vec<u32>>,
+}
+
+impl TiledLayersStorage {
+
+    /// Returns the entities of the layer and all layers nested inside of it.
+    pub fn with_descendants(&self, layer_id: u32) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        let mut pending = vec![layer_id];
+        while let Some(id) = pending.pop() {
+            entities.extend(self.storage.get(&id));
+            pending.extend(self.children.get(&id).into_iter().flatten());
+        }
+        entities
+    }
+}
+
+/// Opacity, tint, visibility and parallax of a layer, composed with all parent groups
+/// like Tiled renders them.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct TiledLayer {
+    pub id: u32,
+    pub name: String,
+    /// Names of the parent groups and the layer joined with `/`, like `Deco/Front`.
+    pub path: String,
+    /// Class of the layer itself, it is not inherited.
+    pub class: Option<String>,
+    pub opacity: f32,
+    pub tint: Color,
+    pub visible: bool,
+    pub parallax: Vec2,
+}
+
+impl Default for TiledLayer {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            name: String::new(),
+            path: String::new(),
+            class: None,
+            opacity: 1.0,
+            tint: Color::WHITE,
+            visible: true,
+            parallax: Vec2::ONE,
+        }
+    }
+}
+
+impl TiledLayer {
+
+    /// Composes a child layer with this layer as its parent.
+    pub fn child(&self, layer: &tiled::LayerData) -> Self {
+        let parent_tint = self.tint.to_srgba();
+        let tint = layer.tint_color
+            .map(|tint| Srgba::rgba_u8(tint.red, tint.green, tint.blue, tint.alpha))
+            .unwrap_or(Srgba::WHITE);
+
+        Self {
+            id: layer.id(),
+            name: layer.name.clone(),
+            path: if self.path.is_empty() { layer.name.clone() } else { format!("{}/{}", self.path, layer.name) },
+            class: layer.user_type.clone(),
+            opacity: self.opacity * layer.opacity,
+            tint: Color::srgba(
+                parent_tint.red * tint.red,
+                parent_tint.green * tint.green,
+                parent_tint.blue * tint.blue,
+                parent_tint.alpha * tint.alpha,
+            ),
+            visible: self.visible && layer.visible,
+            parallax: self.parallax * Vec2::new(layer.parallax_x, layer.parallax_y),
+        }
+    }
+
+    /// Returns `true` if `name` is the name or the path of the layer.
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || self.path == name
+    }
+
+    /// Tint with the opacity applied to the alpha.
+    pub fn color(&self) -> Color {
+        let tint = self.tint.to_srgba();
+        Color::srgba(tint.red, tint.green, tint.blue, tint.alpha * self.opacity)
+    }
 }
 
 #[derive(Component, Default)]
@@ -183,12 +742,33 @@ pub struct TiledMapBundle {
     pub render_settings: TilemapRenderSettings
 }
 
+/// Processes a map again when its file or one of its tilesets and templates changed.
+/// With the `dev` feature the asset folder is watched and a save in Tiled is enough.
 #[coverage(off)]
+fn reload_modified_maps(
+    mut events: EventReader<AssetEvent<TiledMap>>,
+    mut map_query: Query<(&TiledMapHandle, &mut TiledMapLoaded)>,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else { continue };
+        for (map_handle, mut load_state) in map_query.iter_mut() {
+            if map_handle.0.id() == *id {
+                load_state.0 = false;
+            }
+        }
+    }
+}
+
+/// Spawns the layers of the map. The previous layers and all entities of the object layers
+/// are despawned first and the loader systems of the object layers run again. Only a new
+/// version of the same map asset counts as a reload, another map is a fresh load.
+#[coverage(off)]
+#[allow(clippy::too_many_arguments)]
 fn process_maps(
     mut commands: Commands,
     maps: Res<Assets<TiledMap>>,
-    tile_storage_query: Query<(Entity, &mut TileStorage)>,
     mut map_query: Query<(&TiledMapHandle, &mut TiledMapLoaded, &mut TiledLayersStorage, &TilemapRenderSettings)>,
+    tiled_objects: Query<Entity, With<TiledObject>>,
     mut object_layers: ResMut<ObjectLayers>,
     mut level_data: ResMut<LevelData>,
     asset_server: Res<AssetServer>,
@@ -200,246 +780,736 @@ fn process_maps(
         }
 
         if let Some(tiled_map) = maps.get(&map_handle.0) {
-            level_data.map = Some(tiled_map.map.clone());
-            level_data.image_layers.clear();
+            let source = map_handle.0.id();
+            let reloaded = level_data.source == Some(source) && level_data.map.is_some();
+            if let Some(previous) = level_data.map.take() {
+                if reloaded {
+                    info!("Reloaded map: {}", diff_maps(&previous, &tiled_map.map));
+                }
+                for entity in tiled_objects.iter() {
+                    commands.entity(entity).try_despawn();
+                }
+                object_layers.layer_data.clear();
+            }
+
+            *level_data = LevelData {
+                source: Some(source),
+                reloaded,
+                ..LevelData::from_map(&tiled_map.map)
+            };
             load_state.0 = true;
 
+            // Tiles and nested layers are children of their layer entity
             for layer_entity in layer_storage.storage.values() {
-                if let Ok((_, layer_title_storage)) = tile_storage_query.get(*layer_entity) {
-                    for tile in layer_title_storage.iter().flatten() {
-                        commands.entity(*tile).despawn();
+                commands.entity(*layer_entity).try_despawn();
+            }
+            layer_storage.storage.clear();
+            layer_storage.children.clear();
+
+            let mut spawner = LayerSpawner {
+                commands: &mut commands,
+                tiled_map,
+                bounds: level_data.bounds,
+                asset_server: &asset_server,
+                object_layers: &mut object_layers,
+                layer_storage: &mut layer_storage,
+                render_settings: *render_settings,
+                draw_index: 0,
+            };
+            spawner.spawn_layers(tiled_map.map.layers(), None, &TiledLayer::default());
+        }
+    }
+}
+
+/// Chunk position, size and tiles of one tilemap, finite layers use a single chunk at zero.
+type TilemapTiles<'map> = (IVec2, TilemapSize, Vec<(TilePos, LayerTile<'map>)>);
+
+/// Spawns the layers of a map and recurses into group layers.
+struct LayerSpawner<'a, 'w, 's> {
+    commands: &'a mut Commands<'w, 's>,
+    tiled_map: &'a TiledMap,
+    bounds: IRect,
+    asset_server: &'a AssetServer,
+    object_layers: &'a mut ObjectLayers,
+    layer_storage: &'a mut TiledLayersStorage,
+    render_settings: TilemapRenderSettings,
+    /// Draw order of the next layer, used as z of the layer.
+    draw_index: u32,
+}
+
+impl LayerSpawner<'_, '_, '_> {
+
+    #[coverage(off)]
+    fn spawn_layers<'map>(
+        &mut self,
+        layers: impl Iterator<Item = tiled::Layer<'map>>,
+        parent: Option<(u32, Entity)>,
+        parent_layer: &TiledLayer,
+    ) {
+        for layer in layers {
+            let tiled_layer = parent_layer.child(&layer);
+            let z = self.draw_index as f32;
+            self.draw_index += 1;
+
+            let entity = match layer.layer_type() {
+                tiled::LayerType::Objects(object_layer) => {
+                    let data: Vec<ObjectData> = object_layer.object_data().to_vec();
+                    self.object_layers.layer_data.insert(layer.name.clone(), data);
+                    if self.object_layers.loader_systems.contains_key(&layer.name) {
+                        let system = self.object_layers.loader_systems[&layer.name];
+                        self.commands.run_system(system);
+                        debug!("Loaded system for layer {}", layer.name);
+                    } else {
+                        warn!("No System fond for ( {:?} )", layer.name);
                     }
+                    continue;
                 }
-                commands.entity(*layer_entity).despawn();
+                tiled::LayerType::Image(image_layer) => {
+                    let Some(entity) = self.spawn_image_layer(&layer, &image_layer, &tiled_layer, z) else { continue };
+                    entity
+                }
+                tiled::LayerType::Tiles(tile_layer) => {
+                    let entity = self.spawn_tile_layer(&layer, &tile_layer, &tiled_layer, z);
+                    let base = Vec2::new(layer.offset_x, -layer.offset_y);
+                    if let Some(parallax) = self.parallax(&tiled_layer, base, BVec2::FALSE, Vec2::ZERO) {
+                        self.commands.entity(entity).insert(parallax);
+                    }
+                    entity
+                }
+                tiled::LayerType::Group(group_layer) => {
+                    let entity = self.commands.spawn((
+                        Name::new(format!("GroupLayer: {}", layer.name)),
+                        Transform::from_xyz(layer.offset_x, -layer.offset_y, 0.0),
+                        GlobalTransform::IDENTITY,
+                    )).id();
+                    self.layer_storage.children.entry(layer.id()).or_default();
+                    self.spawn_layers(group_layer.layers(), Some((layer.id(), entity)), &tiled_layer);
+                    entity
+                }
+            };
+
+            let visibility = if layer.visible { Visibility::Inherited } else { Visibility::Hidden };
+            self.commands.entity(entity).insert((tiled_layer, visibility));
+            if let Some((parent_id, parent_entity)) = parent {
+                self.commands.entity(entity).insert(ChildOf(parent_entity));
+                self.layer_storage.children.entry(parent_id).or_default().push(layer.id());
             }
+            self.layer_storage.storage.insert(layer.id(), entity);
+        }
+    }
 
-            for (tileset_index, tileset) in tiled_map.map.tilesets().iter().enumerate() {
-                let tilemap_texture = tiled_map.tilemap_textures.get(&tileset_index).unwrap();
+    #[coverage(off)]
+    fn spawn_image_layer(&mut self, layer: &tiled::Layer, image_layer: &tiled::ImageLayer, tiled_layer: &TiledLayer, z: f32) -> Option<Entity> {
+        let Some(img) = &image_layer.image else {
+            warn!("Image layer '{}' has no image yet!.", layer.name);
+            return None;
+        };
+        let Some(path) = img.source.to_str() else {
+            warn!("Image layer '{}' is not Supported.", layer.name);
+            return None;
+        };
+        let texture: Handle<Image> = self.asset_server.load(normalize_asset_path(Path::new(path)));
+        let color = tiled_layer.color();
+        let parallax = self.parallax(
+            tiled_layer,
+            Vec2::new(layer.offset_x, layer.offset_y),
+            BVec2::new(image_layer.repeat_x, image_layer.repeat_y),
+            Vec2::new(img.width as f32, img.height as f32),
+        );
 
-                let tile_size = TilemapTileSize {
-                    x: tileset.tile_width as f32,
-                    y: tileset.tile_height as f32
-                };
+        let map_px_w = self.bounds.max.x as f32 * self.tiled_map.map.tile_width  as f32;
+        let map_px_h = self.bounds.max.y as f32 * self.tiled_map.map.tile_height as f32;
 
-                let tile_spacing = TilemapSpacing {
-                    x: tileset.spacing as f32,
-                    y: tileset.spacing as f32
-                };
+        let iw = img.width  as f32;
+        let ih = img.height as f32;
+
+        let (iw, ih) = if iw > 0.0 && ih > 0.0 {
+            (iw, ih)
+        } else {
+            (0.0, 0.0)
+        };
+
+        let parent = self.commands.spawn((
+            Name::new(format!("ImageLayer: {}", layer.name)),
+            Sprite {
+                anchor: Anchor::BottomLeft,
+                image: texture.clone(),
+                color,
+                ..Default::default()
+            },
+            Transform::from_xyz(
+                layer.offset_x,
+                layer.offset_y,
+                z,
+            ),
+            GlobalTransform::IDENTITY,
+            InheritedVisibility::VISIBLE,
+        ))
+            .id();
+
+        if (image_layer.repeat_x || image_layer.repeat_y) && iw > 0.0 && ih > 0.0 {
+            let tiles_x = if image_layer.repeat_x {
+                ((map_px_w - layer.offset_x).max(0.0) / iw).ceil().max(1.0) as u32
+            } else { 1 };
+
+            let tiles_y = if image_layer.repeat_y {
+                ((map_px_h - layer.offset_y).max(0.0) / ih).ceil().max(1.0) as u32
+            } else { 1 };
+
+            // A wrapped parallax offset moves the images by up to one image size
+            let first_x = if image_layer.repeat_x && parallax.is_some() { -1 } else { 0 };
+            let first_y = if image_layer.repeat_y && parallax.is_some() { -1 } else { 0 };
+
+            for iy in first_y..tiles_y as i32 {
+                for ix in first_x..tiles_x as i32 {
+                    if ix == 0 && iy == 0 { continue; }
+                    let dx = iw * ix as f32;
+                    let dy = ih * iy as f32;
+
+                    self.commands.spawn((
+                        Name::new("ImageTile"),
+                        Sprite {
+                            anchor: Anchor::BottomLeft,
+                            image: texture.clone(),
+                            color,
+                            ..Default::default()
+                        },
+                        Transform::from_xyz(dx, dy, 0.0),
+                        GlobalTransform::IDENTITY,
+                        Visibility::Inherited,
+                        InheritedVisibility::VISIBLE,
+                        ChildOf(parent)
+                    ));
+                }
+            }
+        }
+
+        if let Some(parallax) = parallax {
+            self.commands.entity(parent).insert(parallax);
+        }
+        Some(parent)
+    }
+
+    /// Parallax of a layer, `None` if the layer moves with the world.
+    fn parallax(&self, tiled_layer: &TiledLayer, base: Vec2, repeat: BVec2, size: Vec2) -> Option<Parallax> {
+        if tiled_layer.parallax == Vec2::ONE {
+            return None;
+        }
+        let map = &self.tiled_map.map;
+        Some(Parallax {
+            factor: tiled_layer.parallax,
+            origin: Vec2::new(0.0, (map.height * map.tile_height) as f32),
+            base,
+            repeat,
+            size,
+        })
+    }
 
-                for (layer_index, layer) in tiled_map.map.layers().enumerate() {
-                    let offset_x = layer.offset_x;
-                    let offset_y = layer.offset_y;
-
-                    match layer.layer_type() {
-                        // Object Layer
-                        tiled::LayerType::Objects(object_layer) => {
-                            let data: Vec<ObjectData> = object_layer.object_data().iter().cloned().collect();
-                            object_layers.layer_data.insert(layer.name.clone(), data);
-                            if object_layers.loader_systems.contains_key(&layer.name) {
-                                let system = object_layers.loader_systems[&layer.name];
-                                commands.run_system(system);
-                                debug!("Loaded system for layer {}", layer.name);
-                            } else {
-                                warn!("No System fond for ( {:?} )", layer.name);
+    /// Spawns the layer entity with one tilemap per used tileset, infinite layers get
+    /// one tilemap per chunk and tileset.
+    #[coverage(off)]
+    fn spawn_tile_layer(&mut self, layer: &tiled::Layer, tile_layer: &TileLayer, tiled_layer: &TiledLayer, z: f32) -> Entity {
+        let tiled_map = self.tiled_map;
+        let layer_entity = self.commands.spawn((
+            Name::new(format!("TileLayer: {}", layer.name)),
+            Transform::from_xyz(layer.offset_x, -layer.offset_y, z),
+            GlobalTransform::IDENTITY,
+        )).id();
+
+        let grid_size = TilemapGridSize {
+            x: tiled_map.map.tile_width as f32,
+            y: tiled_map.map.tile_height as f32
+        };
+
+        let map_type = match tiled_map.map.orientation {
+            tiled::Orientation::Hexagonal => {
+                TilemapType::Hexagon(HexCoordSystem::Row)
+            }
+            tiled::Orientation::Isometric => {
+                TilemapType::Isometric(IsoCoordSystem::Diamond)
+            }
+            tiled::Orientation::Staggered => {
+                TilemapType::Isometric(IsoCoordSystem::Staggered)
+            }
+            tiled::Orientation::Orthogonal => {
+                TilemapType::Square
+            }
+        };
+
+        for (tileset_index, tileset) in tiled_map.map.tilesets().iter().enumerate() {
+            let Some(tilemap_texture) = tiled_map.tilemap_textures.get(&tileset_index) else { continue };
+            let Some(tile_size) = tiled_map.tile_size(tileset_index) else { continue };
+
+            let tile_spacing = TilemapSpacing {
+                x: tileset.spacing as f32,
+                y: tileset.spacing as f32
+            };
+
+            // Tiles are centered on their cell, Tiled aligns larger tiles at the bottom left
+            let tile_offset = Vec2::new(tile_size.x - grid_size.x, tile_size.y - grid_size.y) * 0.5;
+
+            // Tiles of one tilemap in bevy coordinates, y goes up
+            let mut tilemaps: Vec<TilemapTiles> = Vec::new();
+            match tile_layer {
+                TileLayer::Finite(layer_data) => {
+                    let map_size = TilemapSize { x: layer_data.width(), y: layer_data.height() };
+                    let mut tiles = Vec::new();
+                    for x in 0..map_size.x {
+                        for y in 0..map_size.y {
+                            let Some(layer_tile) = layer_data.get_tile(x as i32, (map_size.y - 1 - y) as i32) else { continue };
+                            if tileset_index == layer_tile.tileset_index() {
+                                tiles.push((TilePos { x, y }, layer_tile));
                             }
                         }
-                        // Background Layer
-                        tiled::LayerType::Image(image_layer) => {
-                            if let Some(img) = &image_layer.image {
-                                if let Some(path) = img.source.to_str() {
-                                    let texture: Handle<Image> = asset_server.load(path);
-
-                                    let opacity = layer.opacity;
-                                    let mut color = Color::WHITE.with_alpha(opacity);
-                                    if let Some(tint) = layer.tint_color {
-                                        let a = tint.alpha; let r = tint.red; let g = tint.green; let b = tint.blue;
-                                        color = Color::srgba(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0,
-                                                             (a as f32 / 255.0) * opacity);
-                                    }
-
-                                    let map_px_w = tiled_map.map.width  as f32 * tiled_map.map.tile_width  as f32;
-                                    let map_px_h = tiled_map.map.height as f32 * tiled_map.map.tile_height as f32;
-
-                                    let iw = img.width  as f32;
-                                    let ih = img.height as f32;
-
-                                    let (iw, ih) = if iw > 0.0 && ih > 0.0 {
-                                        (iw, ih)
-                                    } else {
-                                        (0.0, 0.0)
-                                    };
-
-                                    let parent = commands.spawn((
-                                        Name::new(format!("ImageLayer: {}", layer.name)),
-                                        Sprite {
-                                            anchor: Anchor::BottomLeft,
-                                            image: texture.clone(),
-                                            color,
-                                            ..Default::default()
-                                        },
-                                        Transform::from_xyz(
-                                            layer.offset_x,
-                                            layer.offset_y,
-                                            layer_index as f32,
-                                        ),
-                                        GlobalTransform::IDENTITY,
-                                        Visibility::Visible,
-                                        InheritedVisibility::VISIBLE,
-                                    ))
-                                        .id();
-
-                                    if (image_layer.repeat_x || image_layer.repeat_y) && iw > 0.0 && ih > 0.0 {
-                                        let tiles_x = if image_layer.repeat_x {
-                                            ((map_px_w - layer.offset_x).max(0.0) / iw).ceil().max(1.0) as u32
-                                        } else { 1 };
-
-                                        let tiles_y = if image_layer.repeat_y {
-                                            ((map_px_h - layer.offset_y).max(0.0) / ih).ceil().max(1.0) as u32
-                                        } else { 1 };
-
-                                        for iy in 0..tiles_y {
-                                            for ix in 0..tiles_x {
-                                                if ix == 0 && iy == 0 { continue; }
-                                                let dx = iw * ix as f32;
-                                                let dy = ih * iy as f32;
-
-                                                commands.spawn((
-                                                    Name::new("ImageTile"),
-                                                    Sprite {
-                                                        anchor: Anchor::BottomLeft,
-                                                        image: texture.clone(),
-                                                        color,
-                                                        ..Default::default()
-                                                    },
-                                                    Transform::from_xyz(
-                                                        (layer.offset_x + dx) - layer.offset_x,
-                                                        (layer.offset_y + dy) - layer.offset_y,
-                                                        layer_index as f32,
-                                                    ),
-                                                    GlobalTransform::IDENTITY,
-                                                    Visibility::Visible,
-                                                    InheritedVisibility::VISIBLE,
-                                                    ChildOf(parent)
-                                                ));
-                                            }
-                                        }
-                                    }
-
-                                    layer_storage.storage.insert(layer_index as u32, parent);
-                                } else {
-                                    warn!("Image layer '{}' is not Supported.", layer.name);
+                    }
+                    tilemaps.push((IVec2::ZERO, map_size, tiles));
+                }
+                TileLayer::Infinite(layer_data) => {
+                    let chunk_size = TilemapSize { x: ChunkData::WIDTH, y: ChunkData::HEIGHT };
+                    for ((chunk_x, chunk_y), chunk) in layer_data.chunks() {
+                        let mut tiles = Vec::new();
+                        for x in 0..chunk_size.x {
+                            for y in 0..chunk_size.y {
+                                let Some(layer_tile) = chunk.get_tile(x as i32, (chunk_size.y - 1 - y) as i32) else { continue };
+                                if tileset_index == layer_tile.tileset_index() {
+                                    tiles.push((TilePos { x, y }, layer_tile));
                                 }
-                            } else {
-                                warn!("Image layer '{}' has no image yet!.", layer.name);
                             }
                         }
-                        // Tile Layer
-                        tiled::LayerType::Tiles(tile_layer) => {
-                            if let tiled::TileLayer::Finite(layer_data) = tile_layer {
-                                let map_size = TilemapSize {
-                                    x: tiled_map.map.width,
-                                    y: tiled_map.map.height
-                                };
-
-                                if layer.name.eq(&"Collision") {
-                                    level_data.collision_map = vec![0; map_size.x as usize * map_size.y as usize];
-                                }
+                        tilemaps.push((IVec2::new(chunk_x, chunk_y), chunk_size, tiles));
+                    }
+                }
+            }
 
-                                let grid_size = TilemapGridSize {
-                                    x: tiled_map.map.tile_width as f32,
-                                    y: tiled_map.map.tile_height as f32
-                                };
-
-                                let map_type = match tiled_map.map.orientation {
-                                    tiled::Orientation::Hexagonal => {
-                                        TilemapType::Hexagon(HexCoordSystem::Row)
-                                    }
-                                    tiled::Orientation::Isometric => {
-                                        TilemapType::Isometric(IsoCoordSystem::Diamond)
-                                    }
-                                    tiled::Orientation::Staggered => {
-                                        TilemapType::Isometric(IsoCoordSystem::Staggered)
-                                    }
-                                    tiled::Orientation::Orthogonal => {
-                                        TilemapType::Square
-                                    }
-                                };
-
-                                let mut tile_storage = TileStorage::empty(map_size);
-                                let layer_entity = commands.spawn_empty().id();
-
-                                for x in 0..map_size.x {
-                                    for y in 0..map_size.y {
-                                        let mapped_y = (tiled_map.map.height - 1 - y) as i32;
-                                        let mapped_x = x as i32;
-
-                                        let layer_tile = match layer_data.get_tile(mapped_x, mapped_y) {
-                                            Some(tile) => tile,
-                                            None => continue
-                                        };
-
-                                        if tileset_index != layer_tile.tileset_index() {
-                                            continue;
-                                        }
-
-                                        let layer_tile_data = match layer_data.get_tile_data(mapped_x, mapped_y) {
-                                            Some(tile_data) => tile_data,
-                                            None => continue
-                                        };
-
-                                        let texture_index = match tilemap_texture {
-                                            TilemapTexture::Single(_) => layer_tile.id(),
-                                        };
-
-                                        let tile_pos = TilePos { x, y };
-
-                                        let tile_entity = commands.spawn(
-                                            TileBundle {
-                                                position: tile_pos,
-                                                tilemap_id: TilemapId(layer_entity),
-                                                texture_index: TileTextureIndex(texture_index),
-                                                flip: TileFlip {
-                                                    x: layer_tile_data.flip_h,
-                                                    y: layer_tile_data.flip_v,
-                                                    d: layer_tile_data.flip_d
-                                                },
-                                                ..default()
-                                            }
-                                        ).id();
-
-                                       tile_storage.set(&tile_pos, tile_entity);
-
-                                        if layer.name.eq(&"Collision") {
-                                            level_data.collision_map[(mapped_x + mapped_y * map_size.x as i32) as usize] = 1;
-                                        }
-                                    }
-                                }
+            let map_px_h = tiled_map.map.height as f32 * grid_size.y;
+            for (chunk, size, tiles) in tilemaps {
+                if tiles.is_empty() {
+                    continue;
+                }
 
-                                commands.entity(layer_entity).insert(
-                                    TilemapBundle {
-                                        grid_size,
-                                        size: map_size,
-                                        storage: tile_storage,
-                                        texture: tilemap_texture.clone(),
-                                        tile_size,
-                                        spacing: tile_spacing,
-                                        anchor: TilemapAnchor::BottomLeft,
-                                        transform: Transform::from_xyz(offset_x, -offset_y, layer_index as f32),
-                                        map_type,
-                                        render_settings: *render_settings,
-                                        ..default()
-                                    }
-                                );
-
-                                layer_storage.storage.insert(layer_index as u32, layer_entity);
-                            }
-                        }
-                        _ => {
-                            warn!("Unsupported layer type {}", layer.name);
-                        }
+                let mut tile_storage = TileStorage::empty(size);
+                let tilemap_entity = self.commands.spawn(ChildOf(layer_entity)).id();
+
+                for (tile_pos, layer_tile) in tiles {
+                    let Some(texture_index) = tiled_map.texture_index(tileset_index, layer_tile.id()) else { continue };
+                    let animation = tiled_map.tile_animation(tileset_index, layer_tile.id());
+                    let texture_index = animation.as_ref().map_or(texture_index, TiledTileAnimation::texture_index);
+
+                    let tile_entity = self.commands.spawn((
+                        TileBundle {
+                            position: tile_pos,
+                            tilemap_id: TilemapId(tilemap_entity),
+                            texture_index: TileTextureIndex(texture_index),
+                            color: TileColor(tiled_layer.color()),
+                            flip: TileFlip {
+                                x: layer_tile.flip_h,
+                                y: layer_tile.flip_v,
+                                d: layer_tile.flip_d
+                            },
+                            ..default()
+                        },
+                        ChildOf(tilemap_entity)
+                    )).id();
+
+                    if let Some(animation) = animation {
+                        self.commands.entity(tile_entity).insert(animation);
                     }
+                    tile_storage.set(&tile_pos, tile_entity);
                 }
+
+                let (name, origin) = match tile_layer {
+                    TileLayer::Finite(_) => (format!("Tilemap: {} ({})", layer.name, tileset.name), Vec2::ZERO),
+                    TileLayer::Infinite(_) => (
+                        format!("TileChunk: {} ({}, {})", layer.name, chunk.x, chunk.y),
+                        Vec2::new(
+                            (chunk.x * size.x as i32) as f32 * grid_size.x,
+                            map_px_h - ((chunk.y + 1) * size.y as i32) as f32 * grid_size.y,
+                        ),
+                    ),
+                };
+
+                self.commands.entity(tilemap_entity).insert((
+                    Name::new(name),
+                    TilemapBundle {
+                        grid_size,
+                        size,
+                        storage: tile_storage,
+                        texture: tilemap_texture.clone(),
+                        tile_size,
+                        spacing: tile_spacing,
+                        anchor: TilemapAnchor::BottomLeft,
+                        transform: Transform::from_xyz(origin.x + tile_offset.x, origin.y + tile_offset.y, 0.0),
+                        map_type,
+                        render_settings: self.render_settings,
+                        ..default()
+                    }
+                ));
             }
         }
+
+        layer_entity
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::sleep;
+    use std::time::Duration;
+    use bevy::asset::LoadState;
+    use super::*;
+    use super::layers::{FadeLayer, SetLayerVisible};
+
+    /// Loads a map from the `assets` folder of the workspace through the [`TiledLoader`].
+    fn load_test_map(path: &'static str) -> (App, Handle<TiledMap>) {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin {
+                file_path: concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets").to_string(),
+                ..default()
+            },
+            ImagePlugin::default(),
+        ));
+        app.register_asset_loader(TiledLoader);
+        app.register_asset_loader(TiledJsonLoader);
+        app.init_asset::<TiledMap>();
+        // Registers the image loader of the `ImagePlugin`
+        app.finish();
+        app.cleanup();
+
+        let handle: Handle<TiledMap> = app.world().resource::<AssetServer>().load(path);
+        for _ in 0..500 {
+            app.update();
+            match app.world().resource::<AssetServer>().load_state(&handle) {
+                LoadState::Loaded => break,
+                LoadState::Failed(error) => panic!("Could not load {path}: {error}"),
+                _ => sleep(Duration::from_millis(5)),
+            }
+        }
+        (app, handle)
+    }
+
+    #[test]
+    fn test_normalize_asset_path() {
+        assert_eq!(normalize_asset_path(Path::new("maps/tests/../../tilesets/test.tsx")), PathBuf::from("tilesets/test.tsx"));
+        assert_eq!(normalize_asset_path(Path::new("maps/./map.tmx")), PathBuf::from("maps/map.tmx"));
+        assert_eq!(normalize_asset_path(Path::new("../textures/test.png")), PathBuf::from("../textures/test.png"));
+    }
+
+    #[test]
+    fn test_load_map_with_external_tileset_and_template() {
+        let (app, handle) = load_test_map("maps/tests/external_tileset.tmx");
+        let tiled_map = app.world().resource::<Assets<TiledMap>>().get(&handle).expect("Map is not loaded");
+        let map = &tiled_map.map;
+
+        let tileset = &map.tilesets()[0];
+        assert_eq!(tileset.name, "test");
+        assert_eq!(normalize_asset_path(&tileset.source), PathBuf::from("tilesets/test.tsx"));
+        assert!(tileset.get_tile(2275).is_some_and(|tile| tile.collision.is_some()));
+        assert!(matches!(tiled_map.tilemap_textures.get(&0), Some(TilemapTexture::Single(_))));
+
+        let collision = map.layers().find(|layer| layer.name == "Collision").unwrap();
+        let tiled::LayerType::Tiles(tile_layer) = collision.layer_type() else { panic!("Collision is no tile layer") };
+        assert_eq!(layer_tiles(&tile_layer).len(), 9);
+        assert_eq!(tile_layer.get_tile(6, 2).map(|tile| tile.id()), Some(2275));
+
+        let players = map.layers().find(|layer| layer.name == "Player").unwrap();
+        let tiled::LayerType::Objects(object_layer) = players.layer_type() else { panic!("Player is no object layer") };
+        let spawn = object_layer.objects().next().unwrap();
+        assert_eq!(spawn.name, "Spawn");
+        assert_eq!(spawn.user_type, "spawn");
+        assert_eq!(spawn.properties.get("health"), Some(&tiled::PropertyValue::IntValue(100)));
+    }
+
+    #[test]
+    fn test_json_map_matches_tmx_map() {
+        let (tmx_app, tmx_handle) = load_test_map("maps/tests/parity.tmx");
+        let (json_app, json_handle) = load_test_map("maps/tests/parity.tmj");
+        let tmx = &tmx_app.world().resource::<Assets<TiledMap>>().get(&tmx_handle).expect("TMX map is not loaded").map;
+        let json = &json_app.world().resource::<Assets<TiledMap>>().get(&json_handle).expect("JSON map is not loaded").map;
+
+        let tmx_level = LevelData::from_map(tmx);
+        let json_level = LevelData::from_map(json);
+        assert_eq!(json_level.bounds, tmx_level.bounds);
+        assert_eq!(json_level.collision_map, tmx_level.collision_map);
+        assert_eq!(json_level.collision_map.iter().filter(|cell| **cell != 0).count(), 9);
+
+        assert_eq!((json.width, json.height, json.tile_width, json.tile_height), (tmx.width, tmx.height, tmx.tile_width, tmx.tile_height));
+        assert_eq!(json.properties, tmx.properties);
+
+        // External tilesets only differ in the path of the source file
+        assert_eq!(json.tilesets().len(), tmx.tilesets().len());
+        for (json_tileset, tmx_tileset) in json.tilesets().iter().zip(tmx.tilesets()) {
+            let mut json_tileset = json_tileset.as_ref().clone();
+            json_tileset.source = tmx_tileset.source.clone();
+            assert_eq!(&json_tileset, tmx_tileset.as_ref());
+        }
+
+        let json_layers: Vec<_> = json.layers().collect();
+        let tmx_layers: Vec<_> = tmx.layers().collect();
+        assert_eq!(json_layers.len(), tmx_layers.len());
+        for (json_layer, tmx_layer) in json_layers.iter().zip(&tmx_layers) {
+            assert_eq!(**json_layer, **tmx_layer, "Layer {} differs", tmx_layer.name);
+        }
+    }
+
+    #[test]
+    fn test_load_image_collection_tileset() {
+        let (app, handle) = load_test_map("maps/tests/image_collection.tmx");
+        let tiled_map = app.world().resource::<Assets<TiledMap>>().get(&handle).expect("Map is not loaded");
+
+        let atlas = tiled_map.atlases.get(&0).expect("Collection tileset has no atlas");
+        assert_eq!(atlas.cell_size, UVec2::new(456, 64));
+        assert_eq!(tiled_map.texture_index(0, 0), Some(0));
+        assert_eq!(tiled_map.texture_index(0, 3), Some(1));
+        assert_eq!(tiled_map.texture_index(0, 1), None);
+
+        let Some(TilemapTexture::Single(texture)) = tiled_map.tilemap_textures.get(&0) else { panic!("Atlas texture is missing") };
+        let image = app.world().resource::<Assets<Image>>().get(texture).expect("Atlas image is not loaded");
+        assert_eq!(image.size(), UVec2::new(912, 64));
+    }
+
+    #[test]
+    fn test_pack_tileset_atlas_aligns_images_bottom_left() {
+        let image = |width, height, color: [u8; 4]| Image::new_fill(
+            Extent3d { width, height, depth_or_array_layers: 1 },
+            TextureDimension::D2,
+            &color,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        let (atlas_image, atlas) = pack_tileset_atlas(vec![
+            (5, image(2, 1, [255, 0, 0, 255])),
+            (7, image(1, 2, [0, 255, 0, 255])),
+        ]).unwrap();
+
+        assert_eq!(atlas.cell_size, UVec2::new(2, 2));
+        assert_eq!(atlas.indices, HashMap::from([(5, 0), (7, 1)]));
+        assert_eq!(atlas_image.size(), UVec2::new(4, 2));
+
+        let pixel = |x: u32, y: u32| {
+            let start = ((y * 4 + x) * 4) as usize;
+            atlas_image.data.as_ref().unwrap()[start..start + 4].to_vec()
+        };
+        assert_eq!(pixel(0, 0), vec![0, 0, 0, 0]);
+        assert_eq!(pixel(0, 1), vec![255, 0, 0, 255]);
+        assert_eq!(pixel(1, 1), vec![255, 0, 0, 255]);
+        assert_eq!(pixel(2, 0), vec![0, 255, 0, 255]);
+        assert_eq!(pixel(3, 1), vec![0, 0, 0, 0]);
+
+        assert!(pack_tileset_atlas(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_tile_animation_is_read_from_tileset() {
+        let (app, handle) = load_test_map("maps/tests/parity.tmx");
+        let tiled_map = app.world().resource::<Assets<TiledMap>>().get(&handle).expect("Map is not loaded");
+
+        let animation = tiled_map.tile_animation(0, 0).expect("Tile 0 is animated");
+        assert_eq!(animation.frames, vec![
+            TiledAnimationFrame { texture_index: 0, duration: 0.2 },
+            TiledAnimationFrame { texture_index: 1, duration: 0.2 },
+        ]);
+        assert!(tiled_map.tile_animation(0, 1).is_none());
+    }
+
+    #[test]
+    fn test_tile_animation_respects_frame_durations() {
+        let mut animation = TiledTileAnimation {
+            frames: vec![
+                TiledAnimationFrame { texture_index: 4, duration: 0.1 },
+                TiledAnimationFrame { texture_index: 9, duration: 0.3 },
+            ],
+            frame: 0,
+            elapsed: 0.0,
+        };
+
+        assert_eq!(animation.advance(0.05), None);
+        assert_eq!(animation.advance(0.05), Some(9));
+        assert_eq!(animation.advance(0.2), None);
+        assert_eq!(animation.advance(0.1), Some(4));
+        // A long frame time skips whole loops
+        assert_eq!(animation.advance(0.95), Some(9));
+        assert!((animation.elapsed - 0.05).abs() < 1e-4);
+    }
+
+    /// Loads a map and spawns its layers through `process_maps`.
+    fn spawn_test_map(path: &'static str) -> (App, Entity) {
+        let (mut app, handle) = load_test_map(path);
+        app.init_resource::<LevelData>();
+        app.init_resource::<ObjectLayers>();
+        app.add_systems(Update, process_maps);
+        let map_entity = app.world_mut().spawn(TiledMapBundle {
+            tiled_map: TiledMapHandle(handle),
+            ..default()
+        }).id();
+        app.update();
+        (app, map_entity)
+    }
+
+    #[test]
+    fn test_group_layers_compose_with_their_children() {
+        let (mut app, map_entity) = spawn_test_map("maps/tests/parity.tmx");
+        let storage = app.world().get::<TiledLayersStorage>(map_entity).unwrap();
+        assert_eq!(storage.children.get(&4), Some(&vec![5]));
+        let group = storage.storage[&4];
+        let front = storage.storage[&5];
+        assert_eq!(storage.with_descendants(4).len(), 2);
+
+        let world = app.world_mut();
+        assert_eq!(world.get::<ChildOf>(front).map(ChildOf::parent), Some(group));
+        assert_eq!(world.get::<Transform>(group).unwrap().translation, Vec3::new(2.0, -3.0, 0.0));
+
+        let layer = world.get::<TiledLayer>(front).unwrap();
+        assert_eq!(layer.name, "Front");
+        assert_eq!(layer.opacity, 0.8);
+        assert_eq!(layer.parallax, Vec2::new(0.5, 1.0));
+        assert_eq!(layer.tint.to_srgba().to_u8_array(), [255, 128, 128, 255]);
+        assert!(layer.visible);
+        assert_eq!(world.get::<Parallax>(front).map(|parallax| parallax.factor), Some(Vec2::new(0.5, 1.0)));
+        assert!(world.get::<Parallax>(group).is_none());
+
+        // All tiles of the layer use the water tileset
+        let tilemaps = world.query::<(&ChildOf, &TileStorage)>()
+            .iter(world)
+            .filter(|(child_of, _)| child_of.parent() == front)
+            .count();
+        assert_eq!(tilemaps, 1);
+    }
+
+    #[test]
+    fn test_infinite_map_with_negative_chunks() {
+        let (mut app, _) = spawn_test_map("maps/tests/infinite.tmx");
+
+        let level_data = app.world().resource::<LevelData>();
+        assert_eq!(level_data.bounds, IRect::new(-1, -1, 3, 2));
+        assert_eq!(level_data.collision_map, vec![
+            1, 0, 0, 0,
+            0, 1, 0, 0,
+            0, 0, 0, 1,
+        ]);
+
+        // The negative chunk only uses the water tileset, the chunk at zero both tilesets
+        let world = app.world_mut();
+        let mut chunks: Vec<(String, usize)> = world.query::<(&Name, &TileStorage)>()
+            .iter(world)
+            .map(|(name, storage)| (name.to_string(), storage.iter().flatten().count()))
+            .collect();
+        chunks.sort();
+        assert_eq!(chunks, vec![
+            ("TileChunk: Collision (-1, -1)".to_string(), 1),
+            ("TileChunk: Collision (0, 0)".to_string(), 1),
+            ("TileChunk: Collision (0, 0)".to_string(), 1),
+        ]);
+    }
+
+    #[test]
+    fn test_parallax_offset_wraps_on_repeating_axes() {
+        let mut parallax = Parallax {
+            factor: Vec2::new(0.5, 0.0),
+            origin: Vec2::new(0.0, 100.0),
+            base: Vec2::ZERO,
+            repeat: BVec2::FALSE,
+            size: Vec2::new(40.0, 30.0),
+        };
+        assert_eq!(parallax.offset(Vec2::new(0.0, 100.0)), Vec2::ZERO);
+        assert_eq!(parallax.offset(Vec2::new(100.0, 60.0)), Vec2::new(50.0, -40.0));
+
+        parallax.repeat = BVec2::TRUE;
+        assert_eq!(parallax.offset(Vec2::new(100.0, 60.0)), Vec2::new(10.0, 20.0));
+    }
+
+    #[test]
+    fn test_fade_layer_by_path() {
+        let (mut app, map_entity) = spawn_test_map("maps/tests/parity.tmx");
+        app.add_event::<FadeLayer>();
+        app.add_event::<SetLayerVisible>();
+        app.add_systems(Update, (layers::handle_layer_requests, layers::fade_layers).chain().after(process_maps));
+
+        let storage = app.world().get::<TiledLayersStorage>(map_entity).unwrap();
+        let (group, front) = (storage.storage[&4], storage.storage[&5]);
+        let front_tiles = |world: &mut World| -> Vec<Color> {
+            world.query::<(&ChildOf, &TileColor)>().iter(world)
+                .filter(|(child_of, _)| world.get::<ChildOf>(child_of.parent()).is_some_and(|tilemap| tilemap.parent() == front))
+                .map(|(_, color)| color.0)
+                .collect()
+        };
+
+        let colors = front_tiles(app.world_mut());
+        assert_eq!(colors.len(), 2);
+        assert!(colors.iter().all(|color| (color.alpha() - 0.8).abs() < 1e-4));
+
+        app.world_mut().send_event(FadeLayer { name: "Deco/Front".to_string(), opacity: 0.0, duration: 0.0 });
+        app.world_mut().send_event(SetLayerVisible { name: "Deco".to_string(), visible: false });
+        app.update();
+        app.update();
+
+        assert!(front_tiles(app.world_mut()).iter().all(|color| color.alpha() == 0.0));
+        assert_eq!(app.world().get::<Visibility>(group), Some(&Visibility::Hidden));
+        assert_eq!(app.world().get::<TiledLayer>(front).unwrap().class, None);
+    }
+
+    #[test]
+    fn test_diff_maps_lists_changed_layers() {
+        let (parity_app, parity_handle) = load_test_map("maps/tests/parity.tmx");
+        let (external_app, external_handle) = load_test_map("maps/tests/external_tileset.tmx");
+        let parity = &parity_app.world().resource::<Assets<TiledMap>>().get(&parity_handle).expect("Map is not loaded").map;
+        let external = &external_app.world().resource::<Assets<TiledMap>>().get(&external_handle).expect("Map is not loaded").map;
+
+        let unchanged = diff_maps(parity, parity);
+        assert!(unchanged.is_empty());
+        assert_eq!(unchanged.to_string(), "no changes");
+
+        let diff = diff_maps(external, parity);
+        assert!(diff.resized);
+        assert!(diff.tilesets_changed);
+        assert_eq!(diff.added, vec!["Background", "Objects", "Deco/Front"]);
+        assert_eq!(diff.removed, vec!["Player"]);
+        assert_eq!(diff.changed, vec!["Collision"]);
+        assert_eq!(
+            diff.to_string(),
+            "size changed; tilesets changed; added Background, Objects, Deco/Front; removed Player; changed Collision"
+        );
+    }
+
+    #[test]
+    fn test_modified_map_is_processed_again() {
+        let (mut app, map_entity) = spawn_test_map("maps/tests/parity.tmx");
+        app.add_systems(Update, reload_modified_maps.before(process_maps));
+        let object = app.world_mut().spawn(TiledObject).id();
+        let front = app.world().get::<TiledLayersStorage>(map_entity).unwrap().storage[&5];
+
+        let handle = app.world().get::<TiledMapHandle>(map_entity).unwrap().0.clone();
+        let _ = app.world_mut().resource_mut::<Assets<TiledMap>>().get_mut(&handle);
+        app.update();
+        app.update();
+
+        assert!(app.world().get_entity(object).is_err());
+        assert!(app.world().get_entity(front).is_err());
+        let storage = app.world().get::<TiledLayersStorage>(map_entity).unwrap();
+        assert!(app.world().get::<TiledLayer>(storage.storage[&5]).is_some());
+        assert!(app.world().get::<TiledMapLoaded>(map_entity).unwrap().0);
+        assert!(app.world().resource::<LevelData>().reloaded);
+    }
+
+    #[test]
+    fn test_other_map_is_a_fresh_load() {
+        let (mut app, map_entity) = spawn_test_map("maps/tests/parity.tmx");
+        assert!(!app.world().resource::<LevelData>().reloaded);
+
+        let handle: Handle<TiledMap> = app.world().resource::<AssetServer>().load("maps/tests/external_tileset.tmx");
+        for _ in 0..500 {
+            app.update();
+            if app.world().resource::<AssetServer>().is_loaded_with_dependencies(&handle) {
+                break;
+            }
+            sleep(Duration::from_millis(5));
+        }
+        app.world_mut().entity_mut(map_entity).insert((TiledMapHandle(handle.clone()), TiledMapLoaded(false)));
+        app.update();
+
+        let level_data = app.world().resource::<LevelData>();
+        assert_eq!(level_data.source, Some(handle.id()));
+        assert!(!level_data.reloaded);
+    }
+}
@@ -9,4 +9,9 @@ pub struct DoorSensor;
 pub struct DoorEntered;
 
 #[derive(Resource, Default)]
-pub struct DoorOverlap { pub inside: bool }
\ No newline at end of file
+pub struct DoorOverlap { pub inside: bool }
+
+/// Marks entities which are spawned from an object layer, they are despawned before the
+/// object layers are loaded again on a map reload.
+#[derive(Component)]
+pub struct TiledObject;
@@ -9,6 +9,8 @@ pub trait PropertyValueExt {
     fn as_u32(&self) -> Option<u32>;
     fn as_str(&self) -> Option<&str>;
     fn as_color(&self) -> Option<Color>;
+    /// Returns float and int values as `f32`.
+    fn as_number(&self) -> Option<f32>;
 
     fn bool_or(&self, default: bool) -> bool { self.as_bool().unwrap_or(default) }
     fn f32_or(&self, default: f32) -> f32 { self.as_f32().unwrap_or(default) }
@@ -39,6 +41,13 @@ impl PropertyValueExt for PropertyValue {
     fn as_color(&self) -> Option<Color> {
         match self { PropertyValue::ColorValue(c) => Some(*c), _ => None }
     }
+    fn as_number(&self) -> Option<f32> {
+        match self {
+            PropertyValue::FloatValue(v) => Some(*v),
+            PropertyValue::IntValue(v) => Some(*v as f32),
+            _ => None
+        }
+    }
 }
 
 pub trait ObjectShapeExt {
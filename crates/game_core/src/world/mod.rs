@@ -1,5 +1,9 @@
 #![coverage(off)]
 
+pub mod props;
+pub mod surface;
+pub mod water;
+
 use bevy::prelude::*;
 
 #[coverage(off)]
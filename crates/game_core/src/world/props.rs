@@ -0,0 +1,57 @@
+#![coverage(off)]
+
+use bevy::prelude::*;
+
+/// Marks a dynamic physics prop which was spawned from the `Props` object layer.
+/// Props can be pushed and stood on by the player and press down pressure plates.
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct DynamicProp {
+    pub name: String,
+}
+
+/// A sensor which is pressed while at least one dynamic prop or the player is on it.
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct PressurePlate {
+    pub name: String,
+    pub pressed: bool,
+    pub contacts: u32,
+}
+
+/// Send every time a [`PressurePlate`] is pressed or released.
+#[derive(Event, Debug, Clone)]
+pub struct PressurePlateChanged {
+    pub entity: Entity,
+    pub name: String,
+    pub pressed: bool,
+}
+
+/// A sensor which gives experience to the player on contact and disappears.
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct ExperiencePickup {
+    pub amount: u32,
+}
+
+/// Collision group bits of rope and chain segments. Segments collide with the world
+/// but the player character controller ignores them.
+pub const ROPE_COLLISION_GROUP: u32 = 1 << 1;
+
+/// A rope or chain which is build from a Tiled polyline of the `Ropes` object layer.
+/// The first segment is fixed, all others hang on revolute joints.
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct Rope {
+    pub name: String,
+    pub segments: Vec<Entity>,
+}
+
+/// One link of a [`Rope`]. Only segments with `grabbable` can be grabbed by the player.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct RopeSegment {
+    pub rope: Entity,
+    pub index: usize,
+    pub grabbable: bool,
+}
@@ -0,0 +1,89 @@
+#![coverage(off)]
+
+use bevy::prelude::*;
+use crate::tiled::properties::PropertyValueExt;
+
+/// The kind of surface a collider is made of. Set with the `material`
+/// custom property on a tile of the tileset.
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SurfaceKind {
+    #[default]
+    Default,
+    Ice,
+    Mud,
+    Bounce,
+}
+
+/// Surface properties attached to the tile colliders. The player controller uses the
+/// multipliers to scale its acceleration, deceleration and max speed while grounded.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct SurfaceMaterial {
+    pub kind: SurfaceKind,
+    pub acceleration: f32,
+    pub deceleration: f32,
+    pub speed: f32,
+    /// Vertical launch velocity, `0.0` disables the bounce.
+    pub bounce: f32,
+}
+
+impl Default for SurfaceMaterial {
+    fn default() -> Self {
+        Self {
+            kind: SurfaceKind::Default,
+            acceleration: 1.0,
+            deceleration: 1.0,
+            speed: 1.0,
+            bounce: 0.0,
+        }
+    }
+}
+
+impl SurfaceMaterial {
+
+    /// Returns the preset for a material name like `ice`, `mud` or `bounce`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let material = match name.to_ascii_lowercase().as_str() {
+            "default" => Self::default(),
+            "ice" => Self {
+                kind: SurfaceKind::Ice,
+                acceleration: 0.2,
+                deceleration: 0.05,
+                speed: 1.15,
+                ..default()
+            },
+            "mud" => Self {
+                kind: SurfaceKind::Mud,
+                acceleration: 0.6,
+                deceleration: 2.0,
+                speed: 0.45,
+                ..default()
+            },
+            "bounce" => Self {
+                kind: SurfaceKind::Bounce,
+                bounce: 450.0,
+                ..default()
+            },
+            _ => return None,
+        };
+        Some(material)
+    }
+
+    /// Reads the `material` property of a tile. The preset values can be overridden with
+    /// the properties `acceleration`, `deceleration`, `speed` and `bounce`.
+    pub fn from_properties(properties: &tiled::Properties) -> Option<Self> {
+        let name = properties.get("material")?.as_str()?;
+        let Some(mut material) = Self::from_name(name) else {
+            warn!("Unknown surface material '{}'", name);
+            return None;
+        };
+
+        let value = |key: &str| properties.get(key).and_then(|v| v.as_number());
+        if let Some(v) = value("acceleration") { material.acceleration = v; }
+        if let Some(v) = value("deceleration") { material.deceleration = v; }
+        if let Some(v) = value("speed") { material.speed = v; }
+        if let Some(v) = value("bounce") { material.bounce = v; }
+
+        Some(material)
+    }
+}
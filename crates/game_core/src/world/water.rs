@@ -0,0 +1,35 @@
+#![coverage(off)]
+
+use bevy::prelude::*;
+
+/// All water volumes of the current map in world space. Each source owns its list and
+/// rebuilds it when a map is loaded or reloaded.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct WaterRegions {
+    /// Rectangles of the `Water` object layer.
+    pub object_regions: Vec<Rect>,
+    /// Rows of tiles with the custom property `liquid = true`.
+    pub tile_regions: Vec<Rect>,
+}
+
+impl WaterRegions {
+
+    /// Returns `true` if the point is inside any water region.
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.object_regions.iter().chain(&self.tile_regions).any(|region| region.contains(point))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplashKind {
+    Enter,
+    Exit,
+}
+
+/// Send when an entity enters or leaves a water region.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct WaterSplash {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub kind: SplashKind,
+}
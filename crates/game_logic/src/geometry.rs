@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+
+/// Returns points along the polyline with a distance of `step` between them, measured
+/// along the line. The first point is always included, the last point only if the
+/// length of the line is a multiple of `step`. Repeated points are skipped.
+pub fn resample_polyline(points: &[Vec2], step: f32) -> Vec<Vec2> {
+    let Some(first) = points.first() else { return Vec::new(); };
+    let mut result = vec![*first];
+    let mut carry = 0.0;
+
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let length = a.distance(b);
+        if length <= f32::EPSILON { continue; }
+        let direction = (b - a) / length;
+
+        let mut distance = step - carry;
+        while distance <= length {
+            result.push(a + direction * distance);
+            distance += step;
+        }
+        carry = length - (distance - step);
+    }
+
+    result
+}
+
+// =================================================================================================
+//
+//                                            Unit Tests
+//
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resample_polyline_without_points() {
+        assert!(resample_polyline(&[], 4.0).is_empty());
+        assert_eq!(resample_polyline(&[Vec2::ONE], 4.0), vec![Vec2::ONE]);
+    }
+
+    #[test]
+    fn test_resample_polyline_steps_along_a_line() {
+        let points = resample_polyline(&[Vec2::ZERO, Vec2::new(10.0, 0.0)], 4.0);
+        assert_eq!(points, vec![Vec2::ZERO, Vec2::new(4.0, 0.0), Vec2::new(8.0, 0.0)]);
+
+        let points = resample_polyline(&[Vec2::ZERO, Vec2::new(8.0, 0.0)], 4.0);
+        assert_eq!(points.last(), Some(&Vec2::new(8.0, 0.0)));
+    }
+
+    #[test]
+    fn test_resample_polyline_carries_distance_around_corners() {
+        let points = resample_polyline(&[Vec2::ZERO, Vec2::new(3.0, 0.0), Vec2::new(3.0, 0.0), Vec2::new(3.0, -3.0)], 2.0);
+        assert_eq!(points, vec![Vec2::ZERO, Vec2::new(2.0, 0.0), Vec2::new(3.0, -1.0), Vec2::new(3.0, -3.0)]);
+    }
+}
@@ -0,0 +1,266 @@
+use bevy::prelude::*;
+use game_core::tiled::LevelData;
+use game_core::world::tiled_to_world_position;
+
+/// Read only view on a collision grid like `LevelData.collision_map`. Rows are stored
+/// top to bottom, so `y` grows downwards. Every value other than `0` is solid. Cell
+/// `(0, 0)` is the top left corner of the level bounds.
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionGrid<'a> {
+    pub width: i32,
+    pub height: i32,
+    pub cells: &'a [i32],
+}
+
+impl<'a> CollisionGrid<'a> {
+
+    pub fn new(width: i32, height: i32, cells: &'a [i32]) -> Self {
+        Self { width, height, cells }
+    }
+
+    /// Returns the grid of the `Collision` layer, `None` while no map is loaded.
+    pub fn from_level(level_data: &'a LevelData) -> Option<Self> {
+        level_data.map.as_ref()?;
+        let (width, height) = (level_data.bounds.width(), level_data.bounds.height());
+        if level_data.collision_map.len() != (width * height) as usize {
+            return None;
+        }
+        Some(Self::new(width, height, &level_data.collision_map))
+    }
+
+    /// Cells left, right and below the grid count as solid, cells above as empty.
+    pub fn is_solid(&self, cell: IVec2) -> bool {
+        if cell.x < 0 || cell.x >= self.width || cell.y >= self.height {
+            return true;
+        }
+        if cell.y < 0 {
+            return false;
+        }
+        self.cells.get((cell.x + cell.y * self.width) as usize).is_some_and(|v| *v != 0)
+    }
+}
+
+/// Conversion between world positions and tile cells. Cells use the Tiled grid relative
+/// to the level bounds where `y` grows downwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridSpace {
+    /// World position of the bottom left corner of the level bounds.
+    pub origin: Vec2,
+    pub tile_size: Vec2,
+    /// Height of the level bounds in tiles.
+    pub height: i32,
+}
+
+impl GridSpace {
+
+    pub fn new(origin: Vec2, tile_size: Vec2, height: i32) -> Self {
+        Self { origin, tile_size, height }
+    }
+
+    /// Returns the space of the loaded map, `None` while no map is loaded.
+    pub fn from_level(level_data: &LevelData) -> Option<Self> {
+        let map = level_data.map.as_ref()?;
+        let tile_size = Vec2::new(map.tile_width as f32, map.tile_height as f32);
+        let bounds = level_data.bounds;
+        let origin = tiled_to_world_position(bounds.min.as_vec2().with_y(bounds.max.y as f32) * tile_size, map);
+        Some(Self::new(origin, tile_size, bounds.height()))
+    }
+
+    pub fn world_to_tile(&self, position: Vec2) -> IVec2 {
+        let local = (position - self.origin) / self.tile_size;
+        IVec2::new(local.x.floor() as i32, self.height - 1 - local.y.floor() as i32)
+    }
+
+    /// Returns the bottom left corner of the cell in world space.
+    pub fn tile_origin(&self, cell: IVec2) -> Vec2 {
+        self.origin + Vec2::new(cell.x as f32, (self.height - 1 - cell.y) as f32) * self.tile_size
+    }
+
+    pub fn tile_center(&self, cell: IVec2) -> Vec2 {
+        self.tile_origin(cell) + self.tile_size * 0.5
+    }
+
+    /// Casts a ray through the grid with a DDA traversal and returns the first solid
+    /// cell within `max_distance`. Starting inside a solid cell hits at distance `0`.
+    /// A ray which leaves the grid upwards hits nothing, so `max_distance` may be infinite.
+    pub fn raycast(&self, grid: &CollisionGrid, origin: Vec2, direction: Vec2, max_distance: f32) -> Option<GridHit> {
+        let direction = direction.normalize_or_zero();
+        let mut cell = self.world_to_tile(origin);
+        if grid.is_solid(cell) {
+            return Some(GridHit { cell, point: origin, normal: Vec2::ZERO, distance: 0.0 });
+        }
+        if direction == Vec2::ZERO {
+            return None;
+        }
+
+        // World cell in y-up space, converted to Tiled rows only for the solid checks
+        let local = origin - self.origin;
+        let mut world_cell = (local / self.tile_size).floor().as_ivec2();
+        let step = IVec2::new(direction.x.signum() as i32, direction.y.signum() as i32);
+        let boundary = |index: i32, step: i32, size: f32| (index + step.max(0)) as f32 * size;
+        let mut t_max = Vec2::new(
+            if direction.x != 0.0 { (boundary(world_cell.x, step.x, self.tile_size.x) - local.x) / direction.x } else { f32::INFINITY },
+            if direction.y != 0.0 { (boundary(world_cell.y, step.y, self.tile_size.y) - local.y) / direction.y } else { f32::INFINITY },
+        );
+        let t_delta = Vec2::new(
+            if direction.x != 0.0 { self.tile_size.x / direction.x.abs() } else { f32::INFINITY },
+            if direction.y != 0.0 { self.tile_size.y / direction.y.abs() } else { f32::INFINITY },
+        );
+
+        loop {
+            let (distance, normal) = if t_max.x < t_max.y {
+                let distance = t_max.x;
+                world_cell.x += step.x;
+                t_max.x += t_delta.x;
+                (distance, Vec2::new(-step.x as f32, 0.0))
+            } else {
+                let distance = t_max.y;
+                world_cell.y += step.y;
+                t_max.y += t_delta.y;
+                (distance, Vec2::new(0.0, -step.y as f32))
+            };
+
+            if distance > max_distance {
+                return None;
+            }
+
+            cell = IVec2::new(world_cell.x, self.height - 1 - world_cell.y);
+            // Everything above the grid is empty and the ray never comes back down
+            if cell.y < 0 && direction.y > 0.0 {
+                return None;
+            }
+            if grid.is_solid(cell) {
+                return Some(GridHit { cell, point: origin + direction * distance, normal, distance });
+            }
+        }
+    }
+
+    /// Returns `true` if no solid cell is between both points.
+    pub fn line_of_sight(&self, grid: &CollisionGrid, from: Vec2, to: Vec2) -> bool {
+        self.raycast(grid, from, to - from, from.distance(to)).is_none()
+    }
+
+    /// Returns the first solid cell straight below the position. The hit point is on
+    /// the top surface of that cell.
+    pub fn first_solid_below(&self, grid: &CollisionGrid, position: Vec2, max_distance: f32) -> Option<GridHit> {
+        self.raycast(grid, position, Vec2::NEG_Y, max_distance)
+    }
+}
+
+/// Result of a [`GridSpace::raycast`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridHit {
+    /// The solid cell in Tiled coordinates.
+    pub cell: IVec2,
+    /// World position where the ray entered the cell.
+    pub point: Vec2,
+    /// Normal of the hit cell face, zero if the ray started inside the cell.
+    pub normal: Vec2,
+    pub distance: f32,
+}
+
+// =================================================================================================
+//
+//                                            Unit Tests
+//
+// =================================================================================================
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Parses a hand-authored grid, `#` is solid and every other character is free.
+    pub(crate) fn parse(rows: &[&str]) -> (i32, i32, Vec<i32>) {
+        let cells = rows.iter()
+            .flat_map(|row| row.chars().map(|c| if c == '#' { 1 } else { 0 }))
+            .collect();
+        (rows[0].len() as i32, rows.len() as i32, cells)
+    }
+
+    const ROWS: [&str; 4] = [
+        "......",
+        "...#..",
+        "......",
+        "######",
+    ];
+
+    fn space() -> GridSpace {
+        GridSpace::new(Vec2::ZERO, Vec2::splat(16.0), 4)
+    }
+
+    #[test]
+    fn test_world_tile_round_trip() {
+        let space = space();
+        assert_eq!(space.world_to_tile(Vec2::new(1.0, 1.0)), IVec2::new(0, 3));
+        assert_eq!(space.world_to_tile(Vec2::new(17.0, 63.0)), IVec2::new(1, 0));
+        assert_eq!(space.tile_origin(IVec2::new(1, 0)), Vec2::new(16.0, 48.0));
+        assert_eq!(space.world_to_tile(space.tile_center(IVec2::new(4, 2))), IVec2::new(4, 2));
+    }
+
+    #[test]
+    fn test_raycast_hits_wall_face() {
+        let (width, height, cells) = parse(&ROWS);
+        let grid = CollisionGrid::new(width, height, &cells);
+
+        let hit = space().raycast(&grid, Vec2::new(8.0, 40.0), Vec2::X, 100.0).unwrap();
+        assert_eq!(hit.cell, IVec2::new(3, 1));
+        assert_eq!(hit.normal, Vec2::NEG_X);
+        assert!((hit.distance - 40.0).abs() < 1e-4);
+        assert!((hit.point.x - 48.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_raycast_respects_max_distance() {
+        let (width, height, cells) = parse(&ROWS);
+        let grid = CollisionGrid::new(width, height, &cells);
+
+        assert!(space().raycast(&grid, Vec2::new(8.0, 40.0), Vec2::X, 39.0).is_none());
+    }
+
+    #[test]
+    fn test_raycast_leaves_grid_upwards() {
+        let (width, height, cells) = parse(&ROWS);
+        let grid = CollisionGrid::new(width, height, &cells);
+
+        assert!(space().raycast(&grid, Vec2::new(8.0, 40.0), Vec2::Y, f32::INFINITY).is_none());
+        assert!(space().raycast(&grid, Vec2::new(8.0, 40.0), Vec2::new(1.0, 4.0), f32::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_raycast_diagonal_and_inside_solid() {
+        let (width, height, cells) = parse(&ROWS);
+        let grid = CollisionGrid::new(width, height, &cells);
+
+        let hit = space().raycast(&grid, Vec2::new(8.0, 52.0), Vec2::new(1.0, -1.0), 200.0).unwrap();
+        assert_eq!(hit.cell, IVec2::new(2, 3));
+        assert_eq!(hit.normal, Vec2::Y);
+
+        let inside = space().raycast(&grid, Vec2::new(8.0, 8.0), Vec2::X, 10.0).unwrap();
+        assert_eq!(inside.distance, 0.0);
+    }
+
+    #[test]
+    fn test_line_of_sight() {
+        let (width, height, cells) = parse(&ROWS);
+        let grid = CollisionGrid::new(width, height, &cells);
+        let space = space();
+
+        assert!(!space.line_of_sight(&grid, Vec2::new(8.0, 40.0), Vec2::new(88.0, 40.0)));
+        assert!(space.line_of_sight(&grid, Vec2::new(8.0, 24.0), Vec2::new(88.0, 24.0)));
+        assert!(space.line_of_sight(&grid, Vec2::new(8.0, 56.0), Vec2::new(88.0, 56.0)));
+    }
+
+    #[test]
+    fn test_first_solid_below() {
+        let (width, height, cells) = parse(&ROWS);
+        let grid = CollisionGrid::new(width, height, &cells);
+
+        let below = space().first_solid_below(&grid, Vec2::new(56.0, 60.0), 100.0).unwrap();
+        assert_eq!(below.cell, IVec2::new(3, 1));
+        assert!((below.point.y - 48.0).abs() < 1e-4);
+
+        let floor = space().first_solid_below(&grid, Vec2::new(8.0, 60.0), 100.0).unwrap();
+        assert_eq!(floor.cell, IVec2::new(0, 3));
+        assert!((floor.point.y - 16.0).abs() < 1e-4);
+    }
+}
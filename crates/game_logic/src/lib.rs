@@ -1,5 +1,13 @@
 #![feature(coverage_attribute)]
 
+pub mod geometry;
+pub mod grid;
+pub mod motion;
+pub mod movement;
+pub mod pathfinding;
+pub mod progression;
+pub mod stats;
+
 use bevy::prelude::*;
 
 pub struct GameLogicPlugin;
@@ -0,0 +1,96 @@
+use game_core::player::{Player, PlayerMotionState};
+
+/// Evaluates the next [`PlayerMotionState`] based on the current state and the
+/// physics result of this tick. `Dead` is terminal and will never be left.
+pub fn next_motion_state(player: &Player, attacking: bool) -> PlayerMotionState {
+    let motion = &player.motion;
+    let physic = &player.physic;
+
+    if motion.state == PlayerMotionState::Dead || player.stats.health <= 0 {
+        return PlayerMotionState::Dead;
+    }
+
+    if motion.hurt_timer > 0.0 {
+        return PlayerMotionState::Hurt;
+    }
+
+    if attacking {
+        return PlayerMotionState::Attack;
+    }
+
+    if physic.rope.is_some() {
+        return PlayerMotionState::Swing;
+    }
+
+    if physic.in_water && !physic.grounded {
+        return PlayerMotionState::Swim;
+    }
+
+    if !physic.grounded {
+        return match (physic.velocity.y > 0.0, physic.air_jumping) {
+            (true, true) => PlayerMotionState::DoubleJump,
+            (true, false) => PlayerMotionState::Jump,
+            _ => PlayerMotionState::Fall,
+        };
+    }
+
+    if motion.state.is_airborne()
+        || (motion.state == PlayerMotionState::Land && motion.time_in_state < motion.land_time) {
+        return PlayerMotionState::Land;
+    }
+
+    if player.body.horizontal != 0 {
+        PlayerMotionState::Run
+    } else {
+        PlayerMotionState::Idle
+    }
+}
+
+// =================================================================================================
+//
+//                                            Unit Tests
+//
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::*;
+
+    /// Name, setup of the player, attacking and the expected state.
+    type Case = (&'static str, fn(&mut Player), bool, PlayerMotionState);
+
+    /// A grounded player standing still in the idle state.
+    fn standing() -> Player {
+        let mut player = Player::default();
+        player.physic.grounded = true;
+        player.physic.velocity = Vec2::ZERO;
+        player
+    }
+
+    #[test]
+    fn test_next_motion_state() {
+        let cases: [Case; 14] = [
+            ("standing", |_| {}, false, PlayerMotionState::Idle),
+            ("moving", |p| p.body.horizontal = 1, false, PlayerMotionState::Run),
+            ("rising", |p| { p.physic.grounded = false; p.physic.velocity.y = 100.0 }, false, PlayerMotionState::Jump),
+            ("air jump", |p| { p.physic.grounded = false; p.physic.velocity.y = 100.0; p.physic.air_jumping = true }, false, PlayerMotionState::DoubleJump),
+            ("air jump peak", |p| { p.physic.grounded = false; p.physic.velocity.y = 0.0; p.physic.air_jumping = true }, false, PlayerMotionState::Fall),
+            ("falling", |p| { p.physic.grounded = false; p.physic.velocity.y = -100.0 }, false, PlayerMotionState::Fall),
+            ("touch down", |p| p.motion.state = PlayerMotionState::Fall, false, PlayerMotionState::Land),
+            ("landing", |p| { p.motion.state = PlayerMotionState::Land; p.motion.time_in_state = 0.05 }, false, PlayerMotionState::Land),
+            ("landed", |p| { p.motion.state = PlayerMotionState::Land; p.motion.time_in_state = 0.1 }, false, PlayerMotionState::Idle),
+            ("attacking", |_| {}, true, PlayerMotionState::Attack),
+            ("swimming", |p| { p.physic.grounded = false; p.physic.in_water = true }, false, PlayerMotionState::Swim),
+            ("hurt", |p| p.motion.hurt_timer = 0.2, true, PlayerMotionState::Hurt),
+            ("no health", |p| { p.stats.health = 0; p.motion.hurt_timer = 0.2 }, true, PlayerMotionState::Dead),
+            ("dead", |p| { p.motion.state = PlayerMotionState::Dead; p.body.horizontal = 1 }, false, PlayerMotionState::Dead),
+        ];
+
+        for (name, setup, attacking, expected) in cases {
+            let mut player = standing();
+            setup(&mut player);
+            assert_eq!(next_motion_state(&player, attacking), expected, "{name}");
+        }
+    }
+}
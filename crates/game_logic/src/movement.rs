@@ -0,0 +1,138 @@
+/// Rates in units per second² which are used by [`integrate_horizontal`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HorizontalRates {
+    /// Used while input points in the direction of movement.
+    pub acceleration: f32,
+    /// Used without input or while faster than the max speed.
+    pub deceleration: f32,
+    /// Used while input points against the direction of movement.
+    pub turn_around: f32,
+}
+
+/// Moves `current` towards `target` by at most `max_delta` without overshooting.
+pub fn move_towards(current: f32, target: f32, max_delta: f32) -> f32 {
+    if (target - current).abs() <= max_delta {
+        target
+    } else {
+        current + (target - current).signum() * max_delta
+    }
+}
+
+/// Integrates the horizontal velocity for one fixed step.
+///
+/// # Arguments
+/// - `velocity`: The current horizontal velocity.
+/// - `input`: The horizontal input in the range `-1.0..=1.0`.
+/// - `max_speed`: The speed which is reached with full input.
+/// - `rates`: The acceleration rates which should be used, e.g. ground or air rates.
+/// - `dt`: The fixed timestep in seconds.
+///
+/// # Returns
+/// The new horizontal velocity.
+pub fn integrate_horizontal(velocity: f32, input: f32, max_speed: f32, rates: &HorizontalRates, dt: f32) -> f32 {
+    let target = input.clamp(-1.0, 1.0) * max_speed;
+
+    let rate = if input == 0.0 {
+        rates.deceleration
+    } else if velocity != 0.0 && velocity.signum() != input.signum() {
+        rates.turn_around
+    } else if velocity.abs() > target.abs() {
+        rates.deceleration
+    } else {
+        rates.acceleration
+    };
+
+    move_towards(velocity, target, rate * dt)
+}
+
+/// Returns the damage of a landing with `impact_speed`, `0` up to the threshold.
+/// Every started point above the threshold counts as a whole point of damage.
+pub fn fall_damage(impact_speed: f32, threshold: f32, scale: f32) -> i32 {
+    if impact_speed <= threshold {
+        return 0;
+    }
+    ((impact_speed - threshold) * scale).ceil() as i32
+}
+
+// =================================================================================================
+//
+//                                            Unit Tests
+//
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATES: HorizontalRates = HorizontalRates {
+        acceleration: 1600.0,
+        deceleration: 2000.0,
+        turn_around: 3200.0,
+    };
+
+    /// Runs the integration until the target is reached and returns the needed time.
+    fn time_to_reach(start: f32, input: f32, target: f32, rate_hz: f32) -> f32 {
+        let dt = 1.0 / rate_hz;
+        let mut velocity = start;
+        let mut time = 0.0;
+        while velocity != target {
+            velocity = integrate_horizontal(velocity, input, 200.0, &RATES, dt);
+            time += dt;
+            assert!(time < 10.0, "target was never reached");
+        }
+        time
+    }
+
+    #[test]
+    fn test_move_towards_does_not_overshoot() {
+        assert_eq!(move_towards(0.0, 10.0, 4.0), 4.0);
+        assert_eq!(move_towards(8.0, 10.0, 4.0), 10.0);
+        assert_eq!(move_towards(0.0, -10.0, 4.0), -4.0);
+        assert_eq!(move_towards(-8.0, -10.0, 4.0), -10.0);
+    }
+
+    #[test]
+    fn test_time_to_max_speed_is_independent_of_rate() {
+        let expected = 200.0 / RATES.acceleration;
+        for rate_hz in [30.0, 50.0, 60.0, 64.0, 120.0, 144.0, 240.0] {
+            let time = time_to_reach(0.0, 1.0, 200.0, rate_hz);
+            assert!((time - expected).abs() <= 1.0 / rate_hz + 1e-4, "{rate_hz} Hz took {time}s");
+        }
+    }
+
+    #[test]
+    fn test_time_to_stop_is_independent_of_rate() {
+        let expected = 200.0 / RATES.deceleration;
+        for rate_hz in [30.0, 60.0, 144.0] {
+            let time = time_to_reach(200.0, 0.0, 0.0, rate_hz);
+            assert!((time - expected).abs() <= 1.0 / rate_hz + 1e-4, "{rate_hz} Hz took {time}s");
+        }
+    }
+
+    #[test]
+    fn test_turn_around_uses_turn_rate() {
+        let velocity = integrate_horizontal(100.0, -1.0, 200.0, &RATES, 0.01);
+        assert_eq!(velocity, 100.0 - RATES.turn_around * 0.01);
+    }
+
+    #[test]
+    fn test_over_max_speed_decelerates() {
+        let velocity = integrate_horizontal(300.0, 1.0, 200.0, &RATES, 0.01);
+        assert_eq!(velocity, 300.0 - RATES.deceleration * 0.01);
+    }
+
+    #[test]
+    fn test_input_is_clamped() {
+        let velocity = integrate_horizontal(195.0, 4.0, 200.0, &RATES, 0.1);
+        assert_eq!(velocity, 200.0);
+    }
+
+    #[test]
+    fn test_fall_damage_starts_above_threshold() {
+        assert_eq!(fall_damage(0.0, 550.0, 0.25), 0);
+        assert_eq!(fall_damage(550.0, 550.0, 0.25), 0);
+        assert_eq!(fall_damage(550.5, 550.0, 0.25), 1);
+        assert_eq!(fall_damage(590.0, 550.0, 0.25), 10);
+        assert_eq!(fall_damage(591.0, 550.0, 0.25), 11);
+    }
+}
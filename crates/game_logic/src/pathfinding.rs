@@ -0,0 +1,379 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use bevy::prelude::*;
+use crate::grid::CollisionGrid;
+
+/// Extra cost of a jump on top of the travelled tiles.
+const JUMP_PENALTY: f32 = 1.0;
+/// Cost per tile of falling down after walking off an edge.
+const DROP_COST_PER_TILE: f32 = 0.5;
+
+/// Abilities of the agent which uses the graph, all values are in tiles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NavConfig {
+    /// Free cells the agent needs above the ground, including the standing cell.
+    pub agent_height: i32,
+    /// Maximum number of tiles the agent can jump up, `0` disables jumps.
+    pub max_jump_height: i32,
+    /// Maximum horizontal distance of a jump.
+    pub max_jump_distance: i32,
+    /// Maximum number of tiles the agent may fall after walking off an edge.
+    pub max_drop_height: i32,
+}
+
+impl Default for NavConfig {
+    fn default() -> Self {
+        Self {
+            agent_height: 1,
+            max_jump_height: 3,
+            max_jump_distance: 4,
+            max_drop_height: 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LinkKind {
+    Walk,
+    Jump,
+    Drop,
+}
+
+/// Directed edge between two walkable cells.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NavLink {
+    pub to: IVec2,
+    pub kind: LinkKind,
+    pub cost: f32,
+}
+
+/// One cell of a path together with the link which was used to reach it.
+/// The first step of a path is the start cell and always uses [`LinkKind::Walk`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathStep {
+    pub cell: IVec2,
+    pub kind: LinkKind,
+}
+
+/// Platformer navigation graph. Nodes are walkable cells, a free cell with a solid
+/// cell below and enough head room. Nodes are linked by walking to a neighbour,
+/// jumping to a reachable ledge or dropping down from an edge.
+#[derive(Debug, Clone, Default)]
+pub struct NavGraph {
+    nodes: HashMap<IVec2, Vec<NavLink>>,
+}
+
+impl NavGraph {
+
+    pub fn build(grid: &CollisionGrid, config: &NavConfig) -> Self {
+        let mut nodes = HashMap::new();
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let cell = IVec2::new(x, y);
+                if is_walkable(grid, config, cell) {
+                    nodes.insert(cell, Vec::new());
+                }
+            }
+        }
+
+        let cells: Vec<IVec2> = nodes.keys().copied().collect();
+        for cell in cells {
+            let links = build_links(grid, config, cell, &nodes);
+            nodes.insert(cell, links);
+        }
+
+        Self { nodes }
+    }
+
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        self.nodes.contains_key(&cell)
+    }
+
+    pub fn links(&self, cell: IVec2) -> &[NavLink] {
+        self.nodes.get(&cell).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the first walkable cell at or below `cell` within `max_distance` tiles,
+    /// e.g. to find the ground below an airborne agent.
+    pub fn walkable_below(&self, cell: IVec2, max_distance: i32) -> Option<IVec2> {
+        (0..=max_distance)
+            .map(|offset| cell + IVec2::new(0, offset))
+            .find(|candidate| self.is_walkable(*candidate))
+    }
+
+    /// Finds the cheapest path with A*. Both cells have to be walkable.
+    pub fn find_path(&self, start: IVec2, goal: IVec2) -> Option<Vec<PathStep>> {
+        if !self.is_walkable(start) || !self.is_walkable(goal) {
+            return None;
+        }
+
+        let mut open = BinaryHeap::new();
+        let mut came_from: HashMap<IVec2, (IVec2, LinkKind)> = HashMap::new();
+        let mut costs: HashMap<IVec2, f32> = HashMap::from([(start, 0.0)]);
+        open.push(OpenNode { cell: start, cost: 0.0, estimate: heuristic(start, goal) });
+
+        while let Some(current) = open.pop() {
+            if current.cell == goal {
+                return Some(reconstruct(&came_from, start, goal));
+            }
+            if current.cost > costs.get(&current.cell).copied().unwrap_or(f32::INFINITY) {
+                continue;
+            }
+
+            for link in self.links(current.cell) {
+                let cost = current.cost + link.cost;
+                if cost >= costs.get(&link.to).copied().unwrap_or(f32::INFINITY) {
+                    continue;
+                }
+                costs.insert(link.to, cost);
+                came_from.insert(link.to, (current.cell, link.kind));
+                open.push(OpenNode { cell: link.to, cost, estimate: cost + heuristic(link.to, goal) });
+            }
+        }
+
+        None
+    }
+}
+
+/// Every link costs at least its horizontal distance, so this never overestimates.
+fn heuristic(from: IVec2, to: IVec2) -> f32 {
+    (to.x - from.x).abs() as f32
+}
+
+fn reconstruct(came_from: &HashMap<IVec2, (IVec2, LinkKind)>, start: IVec2, goal: IVec2) -> Vec<PathStep> {
+    let mut path = Vec::new();
+    let mut cell = goal;
+    while cell != start {
+        let (previous, kind) = came_from[&cell];
+        path.push(PathStep { cell, kind });
+        cell = previous;
+    }
+    path.push(PathStep { cell: start, kind: LinkKind::Walk });
+    path.reverse();
+    path
+}
+
+fn has_room(grid: &CollisionGrid, config: &NavConfig, cell: IVec2) -> bool {
+    (0..config.agent_height.max(1)).all(|offset| !grid.is_solid(cell - IVec2::new(0, offset)))
+}
+
+fn is_walkable(grid: &CollisionGrid, config: &NavConfig, cell: IVec2) -> bool {
+    has_room(grid, config, cell) && grid.is_solid(cell + IVec2::Y)
+}
+
+fn build_links(grid: &CollisionGrid, config: &NavConfig, cell: IVec2, nodes: &HashMap<IVec2, Vec<NavLink>>) -> Vec<NavLink> {
+    let mut links = Vec::new();
+
+    for direction in [-1, 1] {
+        let side = cell + IVec2::new(direction, 0);
+
+        if nodes.contains_key(&side) {
+            links.push(NavLink { to: side, kind: LinkKind::Walk, cost: 1.0 });
+        } else if has_room(grid, config, side) {
+            // Walk off the edge and fall until the first ground
+            let landing = (1..=config.max_drop_height)
+                .map(|depth| side + IVec2::new(0, depth))
+                .take_while(|candidate| !grid.is_solid(*candidate))
+                .find(|candidate| nodes.contains_key(candidate));
+            if let Some(landing) = landing {
+                let depth = (landing.y - cell.y) as f32;
+                links.push(NavLink { to: landing, kind: LinkKind::Drop, cost: 1.0 + depth * DROP_COST_PER_TILE });
+            }
+        }
+
+        for distance in 1..=config.max_jump_distance {
+            for rise in -config.max_drop_height..=config.max_jump_height {
+                let target = cell + IVec2::new(direction * distance, -rise);
+                // Neighbours on the same row are reached by walking
+                if (distance == 1 && rise <= 0) || !nodes.contains_key(&target) {
+                    continue;
+                }
+                if can_jump(grid, config, cell, target) {
+                    let cost = distance as f32 + rise.max(0) as f32 + JUMP_PENALTY;
+                    links.push(NavLink { to: target, kind: LinkKind::Jump, cost });
+                }
+            }
+        }
+    }
+
+    links
+}
+
+/// Checks a conservative jump arc: straight up to one tile above the higher cell,
+/// horizontally to the column of the target and straight down to the target.
+fn can_jump(grid: &CollisionGrid, config: &NavConfig, from: IVec2, to: IVec2) -> bool {
+    let apex = from.y.min(to.y) - 1;
+    if from.y - apex > config.max_jump_height {
+        return false;
+    }
+
+    let free = |x: i32, y: i32| has_room(grid, config, IVec2::new(x, y));
+    let (left, right) = (from.x.min(to.x), from.x.max(to.x));
+
+    (apex..=from.y).all(|y| free(from.x, y))
+        && (left..=right).all(|x| free(x, apex))
+        && (apex..=to.y).all(|y| free(to.x, y))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct OpenNode {
+    cell: IVec2,
+    cost: f32,
+    estimate: f32,
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenNode {
+    // Reversed so the `BinaryHeap` pops the lowest estimate first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+// =================================================================================================
+//
+//                                            Unit Tests
+//
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::tests::parse;
+
+    fn graph(rows: &[&str], config: NavConfig) -> NavGraph {
+        let (width, height, cells) = parse(rows);
+        NavGraph::build(&CollisionGrid::new(width, height, &cells), &config)
+    }
+
+    fn kinds(path: &[PathStep]) -> Vec<LinkKind> {
+        path.iter().skip(1).map(|step| step.kind).collect()
+    }
+
+    #[test]
+    fn test_walkable_cells_need_ground_below() {
+        let graph = graph(&[
+            "....",
+            "..#.",
+            "####",
+        ], NavConfig::default());
+
+        assert!(graph.is_walkable(IVec2::new(0, 1)));
+        assert!(graph.is_walkable(IVec2::new(2, 0)));
+        assert!(!graph.is_walkable(IVec2::new(0, 0)));
+        assert!(!graph.is_walkable(IVec2::new(2, 1)));
+        assert_eq!(graph.walkable_below(IVec2::new(0, 0), 4), Some(IVec2::new(0, 1)));
+    }
+
+    #[test]
+    fn test_walks_along_a_flat_floor() {
+        let graph = graph(&[
+            ".....",
+            "#####",
+        ], NavConfig::default());
+
+        let path = graph.find_path(IVec2::new(0, 0), IVec2::new(4, 0)).unwrap();
+        assert_eq!(path.len(), 5);
+        assert!(kinds(&path).iter().all(|kind| *kind == LinkKind::Walk));
+    }
+
+    #[test]
+    fn test_jumps_onto_a_step() {
+        let graph = graph(&[
+            "....",
+            "....",
+            "..##",
+            "####",
+        ], NavConfig::default());
+
+        let path = graph.find_path(IVec2::new(0, 2), IVec2::new(3, 1)).unwrap();
+        assert!(kinds(&path).contains(&LinkKind::Jump));
+        assert_eq!(path.last().unwrap().cell, IVec2::new(3, 1));
+    }
+
+    #[test]
+    fn test_jumps_over_a_gap() {
+        let graph = graph(&[
+            "......",
+            "##..##",
+            "##..##",
+        ], NavConfig::default());
+
+        let path = graph.find_path(IVec2::new(0, 0), IVec2::new(5, 0)).unwrap();
+        let kinds = kinds(&path);
+        assert_eq!(kinds.iter().filter(|kind| **kind == LinkKind::Jump).count(), 1);
+        assert!(!kinds.contains(&LinkKind::Drop));
+        assert_eq!(path.last().unwrap().cell, IVec2::new(5, 0));
+    }
+
+    #[test]
+    fn test_drops_from_a_ledge_but_can_not_climb_back() {
+        let config = NavConfig { max_jump_height: 1, ..default() };
+        let graph = graph(&[
+            ".....",
+            "##...",
+            "##...",
+            "#####",
+        ], config);
+        assert!(graph.links(IVec2::new(1, 0)).iter().any(|link| link.kind == LinkKind::Drop && link.to == IVec2::new(2, 2)));
+        assert!(graph.find_path(IVec2::new(1, 0), IVec2::new(4, 2)).is_some());
+        assert!(graph.find_path(IVec2::new(4, 2), IVec2::new(1, 0)).is_none());
+    }
+
+    #[test]
+    fn test_blocked_by_a_high_wall() {
+        let graph = graph(&[
+            "...#...",
+            "...#...",
+            "...#...",
+            "...#...",
+            "#######",
+        ], NavConfig::default());
+
+        assert!(graph.find_path(IVec2::new(0, 3), IVec2::new(6, 3)).is_none());
+    }
+
+    #[test]
+    fn test_low_ceiling_blocks_tall_agents() {
+        let rows = [
+            "#####",
+            "..#..",
+            ".....",
+            "#####",
+        ];
+        let start = IVec2::new(0, 2);
+        let goal = IVec2::new(4, 2);
+
+        assert!(graph(&rows, NavConfig::default()).find_path(start, goal).is_some());
+        let tall = NavConfig { agent_height: 2, ..default() };
+        assert!(graph(&rows, tall).find_path(start, goal).is_none());
+    }
+
+    #[test]
+    fn test_prefers_walking_over_jumping() {
+        let graph = graph(&[
+            "....",
+            "####",
+        ], NavConfig::default());
+
+        let path = graph.find_path(IVec2::new(0, 0), IVec2::new(3, 0)).unwrap();
+        assert!(kinds(&path).iter().all(|kind| *kind == LinkKind::Walk));
+    }
+}
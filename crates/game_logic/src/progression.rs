@@ -0,0 +1,82 @@
+use game_core::player::progression::{PlayerProgression, ProgressionConfig};
+
+/// Adds experience and raises the level as long as the next step of the curve is
+/// reached. Returns all reached levels in order, the level stops at the end of the curve.
+pub fn add_experience(progression: &mut PlayerProgression, config: &ProgressionConfig, amount: u32) -> Vec<u32> {
+    progression.xp = progression.xp.saturating_add(amount);
+
+    let mut reached = Vec::new();
+    while let Some(step) = config.step(progression.level + 1) && progression.xp >= step.xp {
+        progression.level += 1;
+        reached.push(progression.level);
+    }
+    reached
+}
+
+/// Experience which is missing for the next level, `None` at the max level.
+pub fn xp_to_next_level(progression: &PlayerProgression, config: &ProgressionConfig) -> Option<u32> {
+    config.step(progression.level + 1).map(|step| step.xp.saturating_sub(progression.xp))
+}
+
+// =================================================================================================
+//
+//                                            Unit Tests
+//
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_core::player::progression::{Ability, LevelStep};
+
+    fn config() -> ProgressionConfig {
+        ProgressionConfig {
+            levels: vec![
+                LevelStep { xp: 50, health: 10, ..Default::default() },
+                LevelStep { xp: 120, unlock: Some(Ability::DoubleJump), ..Default::default() },
+                LevelStep { xp: 200, ..Default::default() },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_level_up_when_threshold_is_reached() {
+        let config = config();
+        let mut progression = PlayerProgression::default();
+
+        assert!(add_experience(&mut progression, &config, 49).is_empty());
+        assert_eq!(add_experience(&mut progression, &config, 1), vec![2]);
+        assert_eq!(progression.level, 2);
+        assert_eq!(config.step(2).unwrap().health, 10);
+    }
+
+    #[test]
+    fn test_multiple_levels_at_once() {
+        let config = config();
+        let mut progression = PlayerProgression::default();
+
+        assert_eq!(add_experience(&mut progression, &config, 130), vec![2, 3]);
+        assert_eq!(xp_to_next_level(&progression, &config), Some(70));
+    }
+
+    #[test]
+    fn test_level_is_capped_at_end_of_curve() {
+        let config = config();
+        let mut progression = PlayerProgression::default();
+
+        assert_eq!(add_experience(&mut progression, &config, 10_000), vec![2, 3, 4]);
+        assert_eq!(progression.level, config.max_level());
+        assert!(add_experience(&mut progression, &config, 10_000).is_empty());
+        assert_eq!(xp_to_next_level(&progression, &config), None);
+    }
+
+    #[test]
+    fn test_unlock_is_only_reported_once() {
+        let mut progression = PlayerProgression::default();
+
+        assert!(progression.unlock(Ability::Dash));
+        assert!(!progression.unlock(Ability::Dash));
+        assert!(progression.has(Ability::Dash));
+        assert!(!progression.has(Ability::WallJump));
+    }
+}
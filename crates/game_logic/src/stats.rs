@@ -0,0 +1,121 @@
+use game_core::stats::{ModifierOp, StatKind, StatModifier, StatusEffect, StatusEffects};
+
+/// Applies all modifiers of `stat` to the base value. Additive values are summed up
+/// first and the sum is multiplied with every multiplicative value. The result is
+/// never negative.
+pub fn compute_stat<'a>(base: f32, stat: StatKind, modifiers: impl IntoIterator<Item = &'a StatModifier>) -> f32 {
+    let (add, multiply) = modifiers.into_iter()
+        .filter(|modifier| modifier.stat == stat)
+        .fold((0.0, 1.0), |(add, multiply), modifier| match modifier.op {
+            ModifierOp::Add => (add + modifier.value, multiply),
+            ModifierOp::Multiply => (add, multiply * modifier.value),
+        });
+    ((base + add) * multiply).max(0.0)
+}
+
+/// Same as [`compute_stat`] for all modifiers of the active effects.
+pub fn compute_effect_stat(base: f32, stat: StatKind, effects: &StatusEffects) -> f32 {
+    compute_stat(base, stat, effects.modifiers(stat))
+}
+
+/// Reduces the damage of a hit by the defense. A hit always deals at least one point.
+pub fn mitigate_damage(damage: i32, defense: i32) -> i32 {
+    if damage <= 0 {
+        return 0;
+    }
+    (damage - defense.max(0)).max(1)
+}
+
+/// Advances all effects by `dt` seconds, removes expired effects and returns the
+/// whole damage points of damage over time effects.
+pub fn tick_effects(effects: &mut StatusEffects, dt: f32) -> i32 {
+    let mut damage = 0;
+    for effect in effects.effects.iter_mut() {
+        let active = effect_time(effect, dt);
+        effect.pending_damage += effect.damage_per_second * active;
+        let whole = effect.pending_damage.floor();
+        effect.pending_damage -= whole;
+        damage += whole as i32;
+        effect.remaining -= dt;
+    }
+    effects.effects.retain(|effect| !effect.is_expired());
+    damage
+}
+
+/// Time of this tick the effect was still active.
+fn effect_time(effect: &StatusEffect, dt: f32) -> f32 {
+    if effect.duration.is_some() { dt.min(effect.remaining.max(0.0)) } else { dt }
+}
+
+// =================================================================================================
+//
+//                                            Unit Tests
+//
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modifier(stat: StatKind, op: ModifierOp, value: f32) -> StatModifier {
+        StatModifier { stat, op, value }
+    }
+
+    #[test]
+    fn test_additive_then_multiplicative() {
+        let modifiers = [
+            modifier(StatKind::Speed, ModifierOp::Multiply, 0.5),
+            modifier(StatKind::Speed, ModifierOp::Add, 50.0),
+            modifier(StatKind::Speed, ModifierOp::Multiply, 2.0),
+            modifier(StatKind::Damage, ModifierOp::Add, 100.0),
+        ];
+
+        assert_eq!(compute_stat(150.0, StatKind::Speed, &modifiers), 200.0);
+        assert_eq!(compute_stat(10.0, StatKind::Damage, &modifiers), 110.0);
+        assert_eq!(compute_stat(10.0, StatKind::Defense, &modifiers), 10.0);
+    }
+
+    #[test]
+    fn test_stat_is_never_negative() {
+        let modifiers = [modifier(StatKind::Defense, ModifierOp::Add, -20.0)];
+        assert_eq!(compute_stat(5.0, StatKind::Defense, &modifiers), 0.0);
+    }
+
+    #[test]
+    fn test_same_effect_refreshes_instead_of_stacking() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::slow(0.5, 1.0));
+        effects.apply(StatusEffect::slow(0.5, 3.0));
+
+        assert_eq!(effects.effects.len(), 1);
+        assert_eq!(effects.effects[0].remaining, 3.0);
+        assert_eq!(compute_effect_stat(200.0, StatKind::Speed, &effects), 100.0);
+    }
+
+    #[test]
+    fn test_poison_deals_whole_points_over_time() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::poison(2.5, 2.0));
+
+        let damage: i32 = (0..12).map(|_| tick_effects(&mut effects, 0.25)).sum();
+        assert_eq!(damage, 5);
+        assert!(effects.effects.is_empty());
+    }
+
+    #[test]
+    fn test_permanent_effect_does_not_expire() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::new("blessing", None).with_modifier(StatKind::Health, ModifierOp::Add, 20.0));
+
+        tick_effects(&mut effects, 100.0);
+        assert_eq!(compute_effect_stat(100.0, StatKind::Health, &effects), 120.0);
+    }
+
+    #[test]
+    fn test_mitigate_damage() {
+        assert_eq!(mitigate_damage(10, 3), 7);
+        assert_eq!(mitigate_damage(10, 30), 1);
+        assert_eq!(mitigate_damage(0, 3), 0);
+        assert_eq!(mitigate_damage(10, -5), 10);
+    }
+}
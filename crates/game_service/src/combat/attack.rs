@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use game_core::combat::{AttackPhase, AttackStarted, Attacker, HITBOX_COLLISION_GROUP, HitConfirmed, HitStop, Hitbox, Hurtbox};
+use game_core::states::AppState;
+
+pub struct CombatAttackService;
+
+impl Plugin for CombatAttackService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, (
+                tick_hit_stop,
+                update_attacks.after(tick_hit_stop),
+                despawn_orphan_hitboxes,
+            ).run_if(in_state(AppState::Preload)))
+
+            .add_systems(Update, detect_hits.run_if(in_state(AppState::Preload)));
+    }
+}
+
+#[coverage(off)]
+fn tick_hit_stop(
+    time: Res<Time<Fixed>>,
+    mut commands: Commands,
+    mut hit_stop_query: Query<(Entity, &mut HitStop)>,
+) {
+    for (entity, mut hit_stop) in hit_stop_query.iter_mut() {
+        hit_stop.remaining -= time.delta_secs();
+        if hit_stop.remaining <= 0.0 {
+            commands.entity(entity).remove::<HitStop>();
+        }
+    }
+}
+
+/// Starts requested attacks and advances running ones. The hitbox sensor exists
+/// only during the active frames and follows the attacker.
+#[coverage(off)]
+fn update_attacks(
+    time: Res<Time<Fixed>>,
+    mut commands: Commands,
+    mut attacker_query: Query<(Entity, &Transform, &mut Attacker), Without<HitStop>>,
+    mut hitbox_query: Query<&mut Transform, (With<Hitbox>, Without<Attacker>)>,
+    mut writer: EventWriter<AttackStarted>,
+) {
+    for (entity, transform, mut attacker) in attacker_query.iter_mut() {
+        if attacker.requested && !attacker.is_attacking() {
+            attacker.elapsed = Some(0.0);
+            writer.write(AttackStarted { attacker: entity, team: attacker.team });
+        }
+        attacker.requested = false;
+
+        let Some(elapsed) = attacker.elapsed else { continue };
+        let phase = attacker.attack.phase(elapsed);
+        let center = transform.translation.truncate() + attacker.attack.offset * Vec2::new(attacker.facing, 1.0);
+
+        match (phase == AttackPhase::Active, attacker.hitbox) {
+            (true, None) => {
+                let half_size = attacker.attack.half_size;
+                let hitbox = commands.spawn((
+                    Name::new("Hitbox"),
+                    Hitbox {
+                        owner: entity,
+                        team: attacker.team,
+                        damage: attacker.attack.damage(attacker.damage),
+                        hit_stop: attacker.attack.hit_stop,
+                        knockback: attacker.attack.knockback,
+                        hits: Vec::new(),
+                    },
+                    Transform::from_translation(center.extend(0.0)),
+                    GlobalTransform::IDENTITY,
+                    Collider::cuboid(half_size.x, half_size.y),
+                    Sensor,
+                    ActiveEvents::COLLISION_EVENTS,
+                    ActiveCollisionTypes::all(),
+                    CollisionGroups::new(Group::from_bits_truncate(HITBOX_COLLISION_GROUP), Group::ALL),
+                )).id();
+                attacker.hitbox = Some(hitbox);
+            }
+            (true, Some(hitbox)) => {
+                if let Ok(mut hitbox_transform) = hitbox_query.get_mut(hitbox) {
+                    hitbox_transform.translation = center.extend(0.0);
+                }
+            }
+            (false, Some(hitbox)) => {
+                commands.entity(hitbox).despawn();
+                attacker.hitbox = None;
+            }
+            (false, None) => {}
+        }
+
+        attacker.elapsed = if phase == AttackPhase::Finished { None } else { Some(elapsed + time.delta_secs()) };
+    }
+}
+
+#[coverage(off)]
+fn despawn_orphan_hitboxes(
+    mut commands: Commands,
+    hitbox_query: Query<(Entity, &Hitbox)>,
+    attacker_query: Query<(), With<Attacker>>,
+) {
+    for (entity, hitbox) in hitbox_query.iter() {
+        if !attacker_query.contains(hitbox.owner) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Confirms hits of hitboxes on hurtboxes of another team and freezes attacker
+/// and target for the hit stop of the attack.
+#[coverage(off)]
+fn detect_hits(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut hitbox_query: Query<&mut Hitbox>,
+    hurtbox_query: Query<(&Hurtbox, &GlobalTransform)>,
+    transform_query: Query<&GlobalTransform>,
+    mut writer: EventWriter<HitConfirmed>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(a, b, _) = event else { continue };
+        let (hitbox_entity, target) = if hitbox_query.contains(*a) {
+            (*a, *b)
+        } else if hitbox_query.contains(*b) {
+            (*b, *a)
+        } else {
+            continue;
+        };
+
+        let Ok((hurtbox, target_transform)) = hurtbox_query.get(target) else { continue };
+        let Ok(mut hitbox) = hitbox_query.get_mut(hitbox_entity) else { continue };
+        if hurtbox.team == hitbox.team || target == hitbox.owner || hitbox.hits.contains(&target) {
+            continue;
+        }
+        hitbox.hits.push(target);
+
+        let position = target_transform.translation().truncate();
+        let owner_x = transform_query.get(hitbox.owner).map_or(position.x, |owner| owner.translation().x);
+        let direction = if position.x < owner_x { -1.0 } else { 1.0 };
+
+        if hitbox.hit_stop > 0.0 {
+            let hit_stop = HitStop { remaining: hitbox.hit_stop };
+            commands.entity(hitbox.owner).try_insert(hit_stop);
+            commands.entity(target).try_insert(hit_stop);
+        }
+
+        writer.write(HitConfirmed {
+            attacker: hitbox.owner,
+            target,
+            damage: hitbox.damage,
+            knockback: hitbox.knockback,
+            direction,
+            position,
+        });
+    }
+}
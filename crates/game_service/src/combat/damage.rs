@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use game_core::combat::{DamageNumber, HitConfirmed};
+use game_core::enemy::{Enemy, EnemyDefeated};
+use game_core::player::{Player, PlayerTuning};
+use game_core::states::AppState;
+use game_logic::stats::mitigate_damage;
+
+const DAMAGE_NUMBER_LIFETIME: f32 = 0.8;
+const DAMAGE_NUMBER_RISE: f32 = 30.0;
+
+pub struct CombatDamageService;
+
+impl Plugin for CombatDamageService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (apply_hits, update_damage_numbers).run_if(in_state(AppState::Preload)));
+    }
+}
+
+/// Applies the damage of confirmed hits to players and enemies and spawns a
+/// damage number at the target. The defense of the player reduces the damage,
+/// enemies without health are despawned. Hits on a dead player are ignored.
+#[coverage(off)]
+fn apply_hits(
+    mut commands: Commands,
+    tuning: Res<PlayerTuning>,
+    mut reader: EventReader<HitConfirmed>,
+    mut player_query: Query<&mut Player>,
+    mut enemy_query: Query<&mut Enemy>,
+    mut writer: EventWriter<EnemyDefeated>,
+) {
+    for hit in reader.read() {
+        let (damage, color) = if let Ok(mut player) = player_query.get_mut(hit.target) {
+            if player.is_dead() {
+                continue;
+            }
+            let damage = mitigate_damage(hit.damage, player.stats.defense);
+            player.stats.health -= damage;
+            player.motion.hurt_timer = tuning.0.hurt_time;
+            player.physic.velocity.x = hit.direction * hit.knockback;
+            (damage, Color::srgb(1.0, 0.3, 0.3))
+        } else if let Ok(mut enemy) = enemy_query.get_mut(hit.target) {
+            if enemy.health <= 0 {
+                continue;
+            }
+            enemy.health -= hit.damage;
+            if enemy.health <= 0 {
+                writer.write(EnemyDefeated { enemy: hit.target, kind: enemy.kind.clone(), position: hit.position, xp: enemy.xp });
+                commands.entity(hit.target).despawn();
+            }
+            (hit.damage, Color::WHITE)
+        } else {
+            continue;
+        };
+
+        commands.spawn((
+            Name::new("DamageNumber"),
+            DamageNumber { lifetime: DAMAGE_NUMBER_LIFETIME, remaining: DAMAGE_NUMBER_LIFETIME },
+            Text2d::new(damage.to_string()),
+            TextFont { font_size: 10.0, ..default() },
+            TextColor(color),
+            Transform::from_translation(hit.position.extend(20.0)),
+        ));
+    }
+}
+
+#[coverage(off)]
+fn update_damage_numbers(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut number_query: Query<(Entity, &mut DamageNumber, &mut Transform, &mut TextColor)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut number, mut transform, mut color) in number_query.iter_mut() {
+        number.remaining -= dt;
+        if number.remaining <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation.y += DAMAGE_NUMBER_RISE * dt;
+        color.0.set_alpha(number.remaining / number.lifetime);
+    }
+}
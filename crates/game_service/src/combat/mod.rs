@@ -0,0 +1,18 @@
+mod attack;
+mod damage;
+mod projectile;
+
+use bevy::prelude::*;
+use crate::combat::attack::CombatAttackService;
+use crate::combat::damage::CombatDamageService;
+use crate::combat::projectile::CombatProjectileService;
+
+pub struct CombatServiceImpl;
+
+impl Plugin for CombatServiceImpl {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_plugins((CombatAttackService, CombatDamageService, CombatProjectileService));
+    }
+}
@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use game_core::combat::projectile::{FireProjectile, Projectile, ProjectileDefinitions, ProjectilePool};
+use game_core::combat::{HITBOX_COLLISION_GROUP, HitConfirmed, Hurtbox};
+use game_core::states::AppState;
+use game_core::stats::ApplyStatusEffect;
+use game_core::world::props::ROPE_COLLISION_GROUP;
+
+pub struct CombatProjectileService;
+
+impl Plugin for CombatProjectileService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, (
+            fire_projectiles,
+            move_projectiles.after(fire_projectiles),
+        ).run_if(in_state(AppState::Preload)));
+    }
+}
+
+/// Takes a projectile from the [`ProjectilePool`] for every [`FireProjectile`]
+/// request. New entities are only spawned if the pool is empty.
+#[coverage(off)]
+fn fire_projectiles(
+    mut commands: Commands,
+    definitions: Res<ProjectileDefinitions>,
+    mut pool: ResMut<ProjectilePool>,
+    mut reader: EventReader<FireProjectile>,
+    mut projectile_query: Query<(&mut Projectile, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    for request in reader.read() {
+        let Some(definition) = definitions.get(&request.kind) else {
+            warn!("Unknown projectile kind '{}'", request.kind);
+            continue;
+        };
+
+        let projectile = Projectile {
+            kind: request.kind.clone(),
+            owner: request.owner,
+            team: request.team,
+            velocity: request.direction.normalize_or(Vec2::X) * definition.speed,
+            gravity: definition.gravity,
+            remaining: definition.lifetime,
+            pierce_left: definition.pierce,
+            damage: definition.damage,
+            knockback: definition.knockback,
+            hits: Vec::new(),
+            active: true,
+        };
+        let [r, g, b] = definition.color;
+        let sprite = Sprite {
+            color: Color::srgb(r, g, b),
+            custom_size: Some(Vec2::splat(definition.radius * 2.0)),
+            ..default()
+        };
+        let transform = Transform::from_translation(request.position.extend(15.0));
+
+        let reused = pool.free.pop().and_then(|entity| projectile_query.get_mut(entity).ok());
+        if let Some((mut pooled, mut pooled_transform, mut pooled_sprite, mut visibility)) = reused {
+            *pooled = projectile;
+            *pooled_transform = transform;
+            *pooled_sprite = sprite;
+            *visibility = Visibility::Visible;
+        } else {
+            commands.spawn((Name::new("Projectile"), projectile, transform, sprite, Visibility::Visible));
+        }
+    }
+}
+
+/// Moves active projectiles and ray casts the travelled distance of this tick, so
+/// fast projectiles can not tunnel through thin colliders. Hurtboxes of another
+/// team are hit and pierced, every other solid collider stops the projectile.
+#[coverage(off)]
+#[allow(clippy::too_many_arguments)]
+fn move_projectiles(
+    time: Res<Time<Fixed>>,
+    definitions: Res<ProjectileDefinitions>,
+    rapier_context: ReadRapierContext,
+    mut pool: ResMut<ProjectilePool>,
+    hurtbox_query: Query<&Hurtbox>,
+    mut projectile_query: Query<(Entity, &mut Projectile, &mut Transform, &mut Visibility)>,
+    mut writer: EventWriter<HitConfirmed>,
+    mut effect_writer: EventWriter<ApplyStatusEffect>,
+) {
+    let Ok(context) = rapier_context.single() else { return; };
+    let dt = time.delta_secs();
+    let groups = CollisionGroups::new(
+        Group::ALL,
+        Group::ALL.difference(Group::from_bits_truncate(ROPE_COLLISION_GROUP | HITBOX_COLLISION_GROUP))
+    );
+
+    for (entity, mut projectile, mut transform, mut visibility) in projectile_query.iter_mut() {
+        if !projectile.active {
+            continue;
+        }
+
+        projectile.remaining -= dt;
+        projectile.velocity.y -= projectile.gravity * dt;
+
+        let mut origin = transform.translation.truncate();
+        let mut distance = projectile.velocity.length() * dt;
+        let direction = projectile.velocity.normalize_or_zero();
+        let mut stopped = projectile.remaining <= 0.0 || direction == Vec2::ZERO;
+
+        while !stopped && distance > 0.0 {
+            let owner = projectile.owner;
+            let hits = &projectile.hits;
+            let ignored = |collider: Entity| Some(collider) != owner && !hits.contains(&collider);
+            let filter = QueryFilter::default().exclude_sensors().groups(groups).predicate(&ignored);
+            let hit = context.with_query_pipeline(filter, |pipeline| {
+                pipeline.cast_ray(origin, direction, distance, true)
+            });
+
+            let Some((collider, toi)) = hit else {
+                origin += direction * distance;
+                break;
+            };
+            origin += direction * toi;
+            distance -= toi;
+            projectile.hits.push(collider);
+
+            match hurtbox_query.get(collider) {
+                // Allies are passed without using up the pierce
+                Ok(hurtbox) if hurtbox.team == projectile.team => {}
+                Ok(_) => {
+                    writer.write(HitConfirmed {
+                        attacker: owner.unwrap_or(entity),
+                        target: collider,
+                        damage: projectile.damage,
+                        knockback: projectile.knockback,
+                        direction: direction.x.signum(),
+                        position: origin,
+                    });
+                    if let Some(effect) = definitions.get(&projectile.kind).and_then(|definition| definition.effect.clone()) {
+                        effect_writer.write(ApplyStatusEffect { target: collider, effect });
+                    }
+                    if projectile.pierce_left == 0 {
+                        stopped = true;
+                    } else {
+                        projectile.pierce_left -= 1;
+                    }
+                }
+                Err(_) => stopped = true,
+            }
+        }
+
+        transform.translation = origin.extend(transform.translation.z);
+        transform.rotation = Quat::from_rotation_z(projectile.velocity.to_angle());
+
+        if stopped {
+            projectile.active = false;
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+        }
+    }
+}
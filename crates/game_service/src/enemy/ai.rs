@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use game_core::combat::projectile::FireProjectile;
+use game_core::combat::{Attacker, HitStop, Team};
+use game_core::enemy::{Enemy, EnemyAi, EnemyAiState, EnemyAttack, EnemyPhysic};
+use game_core::config::GlobalConfig;
+use game_core::player::Player;
+use game_core::states::AppState;
+use game_core::tiled::LevelData;
+use game_logic::grid::{CollisionGrid, GridSpace};
+use crate::enemy::navigation::EnemyNavigation;
+
+/// Distance at which enemy and player count as touching, the character
+/// controllers keep a small gap between both bodies.
+const CONTACT_MARGIN: f32 = 2.0;
+
+pub struct EnemyAiService;
+
+impl Plugin for EnemyAiService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, (
+            update_enemy_ai,
+            update_enemy_physics.after(update_enemy_ai),
+            handle_enemy_collisions.before(update_enemy_ai),
+        ).run_if(in_state(AppState::Preload)));
+    }
+}
+
+/// Decides the [`EnemyAiState`] of every enemy and sets its horizontal velocity.
+/// Enemies patrol between the edges of their platform, chase the player on sight
+/// and start a melee attack on contact. While chasing, the enemy follows the path
+/// of the [`EnemyNavigation`] and may drop down from edges. A dead player is ignored.
+#[coverage(off)]
+fn update_enemy_ai(
+    time: Res<Time<Fixed>>,
+    level_data: Res<LevelData>,
+    navigation: Res<EnemyNavigation>,
+    player_query: Query<(Entity, &Transform, &Player), Without<Enemy>>,
+    mut enemy_query: Query<(Entity, &Transform, &Enemy, &mut EnemyAi, &mut EnemyPhysic, &mut Attacker)>,
+    mut writer: EventWriter<EnemyAttack>,
+    mut fire_writer: EventWriter<FireProjectile>,
+) {
+    let (Some(space), Some(grid)) = (GridSpace::from_level(&level_data), CollisionGrid::from_level(&level_data)) else { return; };
+    let dt = time.delta_secs();
+    let target = player_query.single().ok().filter(|(_, _, player)| !player.is_dead());
+
+    for (entity, transform, enemy, mut ai, mut physic, mut attacker) in enemy_query.iter_mut() {
+        ai.attack_timer = (ai.attack_timer - dt).max(0.0);
+        let position = transform.translation.truncate();
+
+        let mut state = EnemyAiState::Patrol;
+        let mut player_feet = position;
+        if let Some((player_entity, player_transform, player)) = target {
+            let player_position = player_transform.translation.truncate();
+            let delta = (player_position - position).abs();
+            let reach = physic.half_size + player.body.half_size + Vec2::splat(CONTACT_MARGIN);
+            player_feet = player_position - Vec2::new(0.0, player.body.half_size.y - 1.0);
+
+            if delta.x <= reach.x && delta.y <= reach.y {
+                state = EnemyAiState::Attack;
+                ai.direction = if player_position.x < position.x { -1.0 } else { 1.0 };
+                if ai.attack_timer <= 0.0 && !attacker.is_attacking() {
+                    ai.attack_timer = ai.attack_cooldown;
+                    attacker.facing = ai.direction;
+                    attacker.requested = true;
+                    writer.write(EnemyAttack { enemy: entity, target: player_entity, damage: enemy.damage });
+                }
+            } else if position.distance(player_position) <= ai.aggro_radius
+                && space.line_of_sight(&grid, position, player_position) {
+                state = EnemyAiState::Chase;
+                if let Some(kind) = enemy.projectile.as_ref() && ai.attack_timer <= 0.0 {
+                    ai.attack_timer = ai.attack_cooldown;
+                    fire_writer.write(FireProjectile {
+                        owner: Some(entity),
+                        team: Team::Enemy,
+                        kind: kind.clone(),
+                        position,
+                        direction: player_position - position,
+                    });
+                }
+            }
+        }
+        ai.state = state;
+
+        physic.velocity.x = match state {
+            EnemyAiState::Patrol => {
+                let offset = position.x - ai.home_x;
+                if offset > ai.patrol_range { ai.direction = -1.0; }
+                if offset < -ai.patrol_range { ai.direction = 1.0; }
+                if is_wall_ahead(&space, &grid, position, &physic, ai.direction)
+                    || is_edge_ahead(&space, &grid, position, &physic, ai.direction) {
+                    ai.direction = -ai.direction;
+                }
+                ai.direction * enemy.speed
+            }
+            EnemyAiState::Chase => {
+                let feet = position - Vec2::new(0.0, physic.half_size.y - 1.0);
+                let step = navigation.next_step(&space, &mut ai.path, feet, player_feet, dt);
+                let cell_x = space.world_to_tile(feet).x;
+                ai.direction = match step {
+                    Some(step) if step.cell.x != cell_x => (step.cell.x - cell_x).signum() as f32,
+                    _ if player_feet.x < position.x => -1.0,
+                    _ => 1.0,
+                };
+
+                let may_drop = step.is_some_and(|step| step.drop);
+                if is_wall_ahead(&space, &grid, position, &physic, ai.direction)
+                    || (!may_drop && is_edge_ahead(&space, &grid, position, &physic, ai.direction)) {
+                    0.0
+                } else {
+                    ai.direction * enemy.chase_speed
+                }
+            }
+            EnemyAiState::Attack => 0.0,
+        };
+    }
+}
+
+#[coverage(off)]
+fn update_enemy_physics(
+    time: Res<Time<Fixed>>,
+    global_config: Res<GlobalConfig>,
+    mut enemy_query: Query<(&mut KinematicCharacterController, &mut EnemyPhysic), Without<HitStop>>,
+) {
+    let dt = time.delta_secs();
+    let config = &global_config.gameplay_config.enemy;
+    for (mut kcc, mut physic) in enemy_query.iter_mut() {
+        if !physic.grounded {
+            physic.velocity.y = (physic.velocity.y - config.gravity * dt).max(-config.max_fall_speed);
+        }
+        kcc.translation = Some(physic.velocity * dt);
+    }
+}
+
+#[coverage(off)]
+fn handle_enemy_collisions(
+    mut enemy_query: Query<(&KinematicCharacterControllerOutput, &mut EnemyPhysic)>,
+) {
+    for (output, mut physic) in enemy_query.iter_mut() {
+        physic.grounded = output.grounded;
+        if physic.grounded && physic.velocity.y < 0.0 {
+            physic.velocity.y = 0.0;
+        }
+    }
+}
+
+/// Returns `true` if a solid tile is right in front of the enemy.
+#[coverage(off)]
+fn is_wall_ahead(space: &GridSpace, grid: &CollisionGrid, position: Vec2, physic: &EnemyPhysic, direction: f32) -> bool {
+    let front_x = position.x + direction * (physic.half_size.x + 2.0);
+    grid.is_solid(space.world_to_tile(Vec2::new(front_x, position.y)))
+}
+
+/// Returns `true` if the ground in front of a grounded enemy ends.
+#[coverage(off)]
+fn is_edge_ahead(space: &GridSpace, grid: &CollisionGrid, position: Vec2, physic: &EnemyPhysic, direction: f32) -> bool {
+    if !physic.grounded {
+        return false;
+    }
+
+    let front_x = position.x + direction * (physic.half_size.x + 2.0);
+    space.first_solid_below(grid, Vec2::new(front_x, position.y), physic.half_size.y + 2.0).is_none()
+}
@@ -0,0 +1,18 @@
+mod ai;
+mod navigation;
+mod spawn;
+
+use bevy::prelude::*;
+use crate::enemy::ai::EnemyAiService;
+use crate::enemy::navigation::EnemyNavigationService;
+use crate::enemy::spawn::EnemySpawnService;
+
+pub struct EnemyServiceImpl;
+
+impl Plugin for EnemyServiceImpl {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_plugins((EnemySpawnService, EnemyNavigationService, EnemyAiService));
+    }
+}
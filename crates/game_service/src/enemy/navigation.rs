@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use game_core::enemy::{EnemyAi, EnemyPath, EnemyPathStep};
+use game_core::states::AppState;
+use game_core::tiled::LevelData;
+use game_logic::grid::{CollisionGrid, GridSpace};
+use game_logic::pathfinding::{LinkKind, NavConfig, NavGraph};
+
+/// Seconds after which a chasing enemy plans its path again.
+const REPLAN_INTERVAL: f32 = 0.5;
+
+/// Enemies can not jump, they only walk and drop down from edges.
+const ENEMY_NAV_CONFIG: NavConfig = NavConfig {
+    agent_height: 1,
+    max_jump_height: 0,
+    max_jump_distance: 0,
+    max_drop_height: 6,
+};
+
+/// Navigation graph of the current level, rebuilt whenever [`LevelData`] changes.
+#[derive(Resource, Default)]
+pub struct EnemyNavigation {
+    pub graph: NavGraph,
+}
+
+impl EnemyNavigation {
+
+    /// Returns the next step on the stored path between two world positions at the feet
+    /// of the agents. Positions in the air are projected onto the ground below. The path
+    /// is planned again when the timer ran out, the goal cell changed or the agent left it.
+    pub fn next_step(&self, space: &GridSpace, path: &mut EnemyPath, from: Vec2, to: Vec2, dt: f32) -> Option<EnemyPathStep> {
+        path.replan_timer -= dt;
+        let start = self.graph.walkable_below(space.world_to_tile(from), 1)?;
+        let goal = self.graph.walkable_below(space.world_to_tile(to), ENEMY_NAV_CONFIG.max_drop_height)?;
+
+        // Skip the steps the agent already reached
+        if let Some(index) = path.steps.iter().position(|step| step.cell == start) {
+            path.steps.drain(..=index);
+            path.start = Some(start);
+        }
+
+        if path.goal != Some(goal) || path.start != Some(start) || path.replan_timer <= 0.0 {
+            path.steps = self.graph.find_path(start, goal)
+                .map(|steps| steps.iter()
+                    .skip(1)
+                    .map(|step| EnemyPathStep { cell: step.cell, drop: step.kind == LinkKind::Drop })
+                    .collect())
+                .unwrap_or_default();
+            path.start = Some(start);
+            path.goal = Some(goal);
+            path.replan_timer = REPLAN_INTERVAL;
+        }
+        path.steps.first().copied()
+    }
+}
+
+pub struct EnemyNavigationService;
+
+impl Plugin for EnemyNavigationService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EnemyNavigation>();
+        app.add_systems(Update, rebuild_navigation
+            .run_if(in_state(AppState::Preload))
+            .run_if(resource_changed::<LevelData>));
+    }
+}
+
+#[coverage(off)]
+fn rebuild_navigation(
+    level_data: Res<LevelData>,
+    mut navigation: ResMut<EnemyNavigation>,
+    mut enemies: Query<&mut EnemyAi>,
+) {
+    let Some(grid) = CollisionGrid::from_level(&level_data) else { return; };
+    navigation.graph = NavGraph::build(&grid, &ENEMY_NAV_CONFIG);
+    for mut ai in enemies.iter_mut() {
+        ai.path.invalidate();
+    }
+    debug!("Built enemy navigation with {} walkable cells", navigation.graph.node_count());
+}
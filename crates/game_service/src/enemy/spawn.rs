@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use game_core::combat::{AttackDefinition, Attacker, HITBOX_COLLISION_GROUP, Hurtbox, Team};
+use game_core::config::GlobalConfig;
+use game_core::enemy::{Enemy, EnemyAi, EnemyPhysic};
+use game_core::states::AppState;
+use game_core::tiled::{LevelData, ObjectLayers};
+use game_core::tiled::objects::TiledObject;
+use game_core::tiled::properties::{ObjectShapeExt, PropertyValueExt};
+use game_core::world::props::ROPE_COLLISION_GROUP;
+use game_core::world::tiled_to_world_position;
+
+pub struct EnemySpawnService;
+
+impl Plugin for EnemySpawnService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Preload), init_enemy_loader);
+    }
+}
+
+#[coverage(off)]
+fn init_enemy_loader(
+    mut object_layers: ResMut<ObjectLayers>,
+    mut commands: Commands
+) {
+    object_layers.loader_systems.insert(String::from("Enemies"), commands.register_system(spawn_enemies));
+}
+
+/// Spawns an enemy for every object of the `Enemies` layer. The object type is the
+/// enemy kind, the properties `health`, `damage`, `speed`, `chase_speed`,
+/// `patrol_range`, `aggro_radius` and `xp` override the defaults. Enemies with a
+/// `projectile` property fire that projectile kind while chasing.
+#[coverage(off)]
+fn spawn_enemies(
+    mut commands: Commands,
+    object_layers: Res<ObjectLayers>,
+    level_data: Res<LevelData>,
+    global_config: Res<GlobalConfig>,
+) {
+    let Some(map) = level_data.map.as_ref() else { return; };
+    let Some(objects) = object_layers.layer_data.get("Enemies") else { return; };
+    let config = &global_config.gameplay_config.enemy;
+
+    for object in objects {
+        let width = object.shape.get_width();
+        let height = object.shape.get_height();
+        let size = if width > 0.0 && height > 0.0 { Vec2::new(width, height) } else { Vec2::new(14.0, 20.0) };
+
+        let bottom_left = tiled_to_world_position(Vec2::new(object.x, object.y + height), map);
+        let position = bottom_left + Vec2::new(size.x * 0.5, size.y * 0.5);
+
+        let mut enemy = Enemy::default();
+        let mut ai = EnemyAi {
+            home_x: position.x,
+            ..default()
+        };
+
+        if !object.user_type.is_empty() {
+            enemy.kind = object.user_type.clone();
+        }
+
+        for (name, value) in object.properties.iter() {
+            match name.as_str() {
+                "health" => enemy.health = value.i32_or(enemy.health),
+                "damage" => enemy.damage = value.i32_or(enemy.damage),
+                "speed" => enemy.speed = value.as_number().unwrap_or(enemy.speed),
+                "chase_speed" => enemy.chase_speed = value.as_number().unwrap_or(enemy.chase_speed),
+                "patrol_range" => ai.patrol_range = value.as_number().unwrap_or(ai.patrol_range),
+                "aggro_radius" => ai.aggro_radius = value.as_number().unwrap_or(ai.aggro_radius),
+                "projectile" => enemy.projectile = value.as_str().map(String::from),
+                "xp" => enemy.xp = value.u32_or(enemy.xp),
+                _ => {}
+            }
+        }
+
+        // The swing reaches a bit in front of the body
+        let attack = AttackDefinition {
+            frame_duration: config.attack_frame_duration,
+            startup_frames: config.attack_startup_frames,
+            offset: Vec2::new(size.x * 0.5 + config.attack_reach, 0.0),
+            half_size: Vec2::new(config.attack_half_width, size.y * 0.5),
+            ..default()
+        };
+        let attacker = Attacker::new(Team::Enemy, attack, enemy.damage);
+
+        let radius = size.x * 0.5;
+        let half_height = (size.y * 0.5 - radius).max(1.0);
+
+        commands.spawn((
+            Name::new(format!("Enemy: {}", enemy.kind)),
+            TiledObject,
+            Sprite {
+                color: Color::srgb(0.75, 0.2, 0.25),
+                custom_size: Some(size),
+                ..default()
+            },
+            Transform::from_xyz(position.x, position.y, 9.0),
+            GlobalTransform::IDENTITY,
+            Visibility::Visible,
+            InheritedVisibility::VISIBLE,
+            enemy,
+            ai,
+            attacker,
+            Hurtbox { team: Team::Enemy },
+            EnemyPhysic {
+                half_size: size * 0.5,
+                ..default()
+            },
+            RigidBody::KinematicPositionBased,
+            Collider::capsule_y(half_height, radius.max(1.0)),
+            KinematicCharacterController {
+                offset: CharacterLength::Absolute(config.controller_offset),
+                snap_to_ground: Some(CharacterLength::Absolute(config.snap_to_ground)),
+                filter_groups: Some(CollisionGroups::new(
+                    Group::ALL,
+                    Group::ALL.difference(Group::from_bits_truncate(ROPE_COLLISION_GROUP | HITBOX_COLLISION_GROUP))
+                )),
+                ..default()
+            },
+        ));
+    }
+}
@@ -1,8 +1,12 @@
 #![feature(coverage_attribute)]
 
+mod combat;
+mod enemy;
 mod player;
 
 use bevy::prelude::*;
+use crate::combat::CombatServiceImpl;
+use crate::enemy::EnemyServiceImpl;
 use crate::player::PlayerServiceImpl;
 
 pub struct GameServicePlugin;
@@ -11,7 +15,7 @@ impl Plugin for GameServicePlugin {
 
     #[coverage(off)]
     fn build(&self, app: &mut App) {
-        app.add_plugins(PlayerServiceImpl);
+        app.add_plugins((PlayerServiceImpl, EnemyServiceImpl, CombatServiceImpl));
     }
 
 }
\ No newline at end of file
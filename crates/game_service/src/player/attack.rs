@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use game_core::combat::{AttackDefinition, Attacker};
+use game_core::config::{GlobalConfig, PlayerConfig};
+use game_core::player::{Player, PlayerMotionState};
+use game_core::states::AppState;
+use crate::player::init::handle_player_input;
+
+pub struct PlayerAttackService;
+
+impl Plugin for PlayerAttackService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, handle_attack_input
+            .after(handle_player_input)
+            .run_if(in_state(AppState::Preload)));
+    }
+}
+
+/// Creates the melee attack of the player. The four frames of the `attack`
+/// animation clip are one startup, two active and one recovery frame.
+#[coverage(off)]
+pub(crate) fn player_attack(config: &PlayerConfig) -> AttackDefinition {
+    AttackDefinition {
+        frame_duration: config.attack_frame_duration,
+        hit_stop: config.attack_hit_stop,
+        knockback: config.attack_knockback,
+        ..default()
+    }
+}
+
+#[coverage(off)]
+fn handle_attack_input(
+    input: Res<ButtonInput<KeyCode>>,
+    global_config: Res<GlobalConfig>,
+    mut player_query: Query<(&Player, &mut Attacker)>,
+) {
+    let Ok((player, mut attacker)) = player_query.single_mut() else { return };
+    attacker.damage = player.stats.damage;
+
+    if !attacker.is_attacking() && player.body.horizontal != 0 {
+        attacker.facing = player.body.horizontal.signum() as f32;
+    }
+
+    let blocked = matches!(player.motion.state, PlayerMotionState::Dead | PlayerMotionState::Hurt)
+        || player.physic.rope.is_some();
+    if !blocked && input.just_pressed(global_config.input_config.get_attack_key()) {
+        attacker.requested = true;
+    }
+}
@@ -1,18 +1,27 @@
 use std::collections::HashMap;
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
-use tiled::{LayerType, ObjectShape, TileLayer};
+use tiled::{LayerType, ObjectShape};
 use game_core::animation::{Animation, Animator};
+use game_core::combat::{Attacker, HITBOX_COLLISION_GROUP, HitStop, Hurtbox, Team};
 use game_core::config::GlobalConfig;
-use game_core::player::{Player, PlayerBody, GRAVITY};
+use game_core::config::PlayerConfig;
+use game_core::player::{AirJumped, Landed, Player, PlayerBody, PlayerMotionState, PlayerPhysic, PlayerTuning};
+use game_core::player::progression::Ability;
 use game_core::states::AppState;
-use game_core::tiled::{LevelData, ObjectLayers};
-use game_core::tiled::objects::{DoorEntered, DoorOverlap, DoorSensor};
+use game_core::stats::StatusEffects;
+use game_core::tiled::{layer_tiles, LevelData, ObjectLayers};
+use game_core::tiled::objects::{DoorEntered, DoorOverlap, DoorSensor, TiledObject};
 use game_core::tiled::properties::{ObjectShapeExt, PropertyValueExt};
+use game_core::world::props::ROPE_COLLISION_GROUP;
+use game_core::world::surface::SurfaceMaterial;
 use game_core::world::tiled_to_world_position;
+use game_logic::movement::{integrate_horizontal, move_towards, HorizontalRates};
+use crate::player::attack::player_attack;
 
+/// Parent entity of the tile colliders of the current map.
 #[derive(Resource, Default)]
-struct CollisionBuilt(bool);
+struct TileColliders(Option<Entity>);
 
 pub struct PlayerInitService;
 
@@ -20,7 +29,7 @@ impl Plugin for PlayerInitService {
 
     #[coverage(off)]
     fn build(&self, app: &mut App) {
-        app.init_resource::<CollisionBuilt>();
+        app.init_resource::<TileColliders>();
 
         app.add_systems(OnEnter(AppState::Preload), init_player_loader)
 
@@ -28,7 +37,8 @@ impl Plugin for PlayerInitService {
                 handle_player_input,
                 update_player_animations
                     .after(handle_player_input),
-                build_tile_colliders_once
+                build_tile_colliders.run_if(resource_changed::<LevelData>),
+                apply_player_tuning.run_if(resource_changed::<PlayerTuning>)
             ).run_if(in_state(AppState::Preload)))
 
             .add_systems(Update, (door_observer, door_interact, on_door_entered).run_if(in_state(AppState::Preload)))
@@ -55,7 +65,7 @@ fn door_test(
 ) {
     let Some(map) = level_data.map.as_ref() else { return; };
     let Some(object) = object_layers.get_data("Interact", "DoorTest") else { return; };
-    if !object.user_type.eq_ignore_ascii_case(&"observe") { return; }
+    if !object.user_type.eq_ignore_ascii_case("observe") { return; }
 
     let width = object.shape.get_width();
     let height = object.shape.get_height();
@@ -66,6 +76,7 @@ fn door_test(
     commands.spawn((
         Name::new("DoorSensor"),
         DoorSensor,
+        TiledObject,
         Transform::from_xyz(center.x, center.y - height, 0.0),
         GlobalTransform::IDENTITY,
         Visibility::Visible,
@@ -131,20 +142,39 @@ fn on_door_entered(mut ev: EventReader<DoorEntered>) {
     }
 }
 
+/// Spawns the player at the `Player` object. A reloaded map keeps the existing player
+/// with its position and state, another map moves it to its `Player` object.
 #[coverage(off)]
 fn init_player(
     mut commands: Commands,
     object_layers: Res<ObjectLayers>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     level_data: Res<LevelData>,
+    tuning: Res<PlayerTuning>,
     asset_server: Res<AssetServer>,
+    mut players: Query<(&mut Transform, &mut Player)>,
 ) {
+    if level_data.reloaded && !players.is_empty() {
+        debug!("Player already exists, keeping its position");
+        return;
+    }
+
     if let Some(object) = object_layers.get_data("Entities", "Player") {
         let map = level_data.map.as_ref().unwrap();
 
         let player_size = Vec2::new(13.0, 38.0);
 
         let position = tiled_to_world_position(Vec2::new(object.x, object.y), map) + player_size / 2.0;
+
+        if !players.is_empty() {
+            for (mut transform, mut player) in players.iter_mut() {
+                transform.translation = position.extend(transform.translation.z);
+                player.physic.velocity = Vec2::ZERO;
+                player.physic.rope = None;
+            }
+            return;
+        }
+
         let frame_count = 19;
         let frame_size = UVec2::new(24, 38);
 
@@ -180,6 +210,64 @@ fn init_player(
             looping: false,
         });
 
+        // TODO: The animations below reuse run and jump frames until the sheet has their
+        // own frames, the missing frames are listed in `assets/sprites/player.md`
+        animations.insert("double_jump".to_string(), Animation {
+            start: 15,
+            end: 19,
+            frame_duration: 0.06,
+            looping: false,
+        });
+
+        animations.insert("fall".to_string(), Animation {
+            start: 19,
+            end: 19,
+            frame_duration: 0.1,
+            looping: false,
+        });
+
+        animations.insert("land".to_string(), Animation {
+            start: 15,
+            end: 15,
+            frame_duration: 0.1,
+            looping: false,
+        });
+
+        animations.insert("swim".to_string(), Animation {
+            start: 9,
+            end: 14,
+            frame_duration: 0.18,
+            looping: true,
+        });
+
+        animations.insert("swing".to_string(), Animation {
+            start: 16,
+            end: 16,
+            frame_duration: 0.1,
+            looping: false,
+        });
+
+        animations.insert("attack".to_string(), Animation {
+            start: 15,
+            end: 18,
+            frame_duration: tuning.0.attack_frame_duration,
+            looping: false,
+        });
+
+        animations.insert("hurt".to_string(), Animation {
+            start: 15,
+            end: 15,
+            frame_duration: 0.1,
+            looping: false,
+        });
+
+        animations.insert("dead".to_string(), Animation {
+            start: 15,
+            end: 15,
+            frame_duration: 0.1,
+            looping: false,
+        });
+
 
         let height = player_size.y;
         let width = player_size.x;
@@ -187,12 +275,16 @@ fn init_player(
         let half_height = (height * 0.5) - radius;
 
         let mut player = Player {
+            physic: PlayerPhysic::from_config(&tuning.0),
             body: PlayerBody {
                 horizontal: 0,
                 half_size: player_size / 2.0,
+                swim_up: false,
             },
             ..default()
         };
+        player.base_stats.speed = tuning.0.speed;
+        player.base_stats.jump_force = tuning.0.jump_force;
 
         if !object.properties.is_empty() {
             for (prop_name, prop_value) in object.properties.iter() {
@@ -203,9 +295,26 @@ fn init_player(
                 if prop_name.eq("base_health") {
                     player.base_stats.health = prop_value.i32_or(100);
                 }
+
+                if prop_name.eq("base_damage") {
+                    player.base_stats.damage = prop_value.i32_or(player.base_stats.damage);
+                }
+
+                if prop_name.eq("base_defense") {
+                    player.base_stats.defense = prop_value.i32_or(player.base_stats.defense);
+                }
+
+                // Comma separated abilities which are unlocked from the start, e.g. "dash, wall_jump"
+                if prop_name.eq("abilities") && let Some(names) = prop_value.as_str() {
+                    for ability in names.split(',').filter_map(Ability::from_name) {
+                        player.progression.unlock(ability);
+                    }
+                }
             }
         }
 
+        let attacker = Attacker::new(Team::Player, player_attack(&tuning.0), player.stats.damage);
+
         commands.spawn((
             Name::new("Player"),
             Transform::from_translation(Vec3::new(position.x, position.y, 10.)).with_scale(Vec3::splat(1.0)),
@@ -226,33 +335,77 @@ fn init_player(
                 ..Default::default()
             },
             player,
+            (attacker, Hurtbox { team: Team::Player }, StatusEffects::default()),
             RigidBody::KinematicPositionBased,
             Collider::capsule_y(half_height.max(1.0), radius.max(1.0)),
-            KinematicCharacterController {
-                up: Vec2::Y,
-                offset: CharacterLength::Absolute(0.02),
-                slide: true,
-                snap_to_ground: Some(CharacterLength::Absolute(4.0)),
-                autostep: Some(CharacterAutostep {
-                    max_height: CharacterLength::Absolute(6.0),
-                    min_width: CharacterLength::Absolute(8.0),
-                    include_dynamic_bodies: false
-                }),
-                max_slope_climb_angle: 55f32.to_radians(),
-                min_slope_slide_angle: 65f32.to_radians(),
-                ..default()
-            },
+            character_controller(&tuning.0),
             ActiveEvents::COLLISION_EVENTS,
-            CollisionGroups::new(Group::ALL, Group::ALL)
+            player_collision_groups()
         ));
     } else {
         error!("Player Data not found");
     }
 }
 
+/// Collision groups of the player, rope segments are not solid for the player.
+#[coverage(off)]
+fn player_collision_groups() -> CollisionGroups {
+    CollisionGroups::new(
+        Group::ALL,
+        Group::ALL.difference(Group::from_bits_truncate(ROPE_COLLISION_GROUP))
+    )
+}
+
+/// Creates the character controller of the player from the given tuning.
+#[coverage(off)]
+fn character_controller(config: &PlayerConfig) -> KinematicCharacterController {
+    KinematicCharacterController {
+        custom_mass: Some(config.mass),
+        apply_impulse_to_dynamic_bodies: true,
+        up: Vec2::Y,
+        offset: CharacterLength::Absolute(config.controller_offset),
+        slide: true,
+        snap_to_ground: Some(CharacterLength::Absolute(config.snap_to_ground)),
+        autostep: Some(CharacterAutostep {
+            max_height: CharacterLength::Absolute(config.autostep_max_height),
+            min_width: CharacterLength::Absolute(config.autostep_min_width),
+            include_dynamic_bodies: true
+        }),
+        max_slope_climb_angle: config.max_slope_climb_angle.to_radians(),
+        min_slope_slide_angle: config.min_slope_slide_angle.to_radians(),
+        // Hitboxes are sensors which should never block the movement
+        filter_groups: Some(CollisionGroups::new(
+            Group::ALL,
+            Group::ALL.difference(Group::from_bits_truncate(ROPE_COLLISION_GROUP | HITBOX_COLLISION_GROUP))
+        )),
+        ..default()
+    }
+}
+
+/// Applies a changed [`PlayerTuning`] to all spawned players, e.g. after the
+/// gameplay config was reloaded.
+#[coverage(off)]
+fn apply_player_tuning(
+    tuning: Res<PlayerTuning>,
+    mut player_query: Query<(&mut Player, &mut KinematicCharacterController, &mut Attacker, &mut Animator)>,
+) {
+    for (mut player, mut kcc, mut attacker, mut animator) in player_query.iter_mut() {
+        player.physic.apply_config(&tuning.0);
+        player.base_stats.speed = tuning.0.speed;
+        player.base_stats.jump_force = tuning.0.jump_force;
+        attacker.attack = player_attack(&tuning.0);
+        if let Some(animation) = animator.animations.get_mut("attack") {
+            animation.frame_duration = tuning.0.attack_frame_duration;
+        }
+        let translation = kcc.translation;
+        *kcc = character_controller(&tuning.0);
+        kcc.translation = translation;
+    }
+}
+
 #[coverage(off)]
 fn update_player_animations(
-    mut player_query : Query<(&mut Player,&mut Sprite,&mut Animator)>
+    mut player_query : Query<(&Player,&mut Sprite,&mut Animator)>
 ) {
     if let Ok((player,mut sprite,mut animator)) = player_query.single_mut() {
 
@@ -263,30 +416,33 @@ fn update_player_animations(
             sprite.flip_x = true;
         }
 
-        if !player.physic.grounded {
-            animator.animation = "jump".to_string();
-        }
-        else if player.body.horizontal != 0 {
-            animator.animation = "run".to_string();
-        }
-        else {
-            animator.animation = "idle".to_string();
+        let animation = player.motion.state.animation();
+        if animator.animation != animation {
+            animator.animation = animation.to_string();
         }
     }
 }
 
 #[coverage(off)]
-fn handle_player_input(
+pub(crate) fn handle_player_input(
     input : Res<ButtonInput<KeyCode>>,
     mut player_query : Query<&mut Player>,
-    global_config: Res<GlobalConfig>
+    global_config: Res<GlobalConfig>,
+    tuning: Res<PlayerTuning>,
 ) {
     let left_key = global_config.input_config.get_move_left_key();
     let right_key = global_config.input_config.get_move_right_key();
     let jump_key = global_config.input_config.get_jump_key();
+    let swim_key = global_config.input_config.get_swim_key();
+    let dash_key = global_config.input_config.get_dash_key();
 
     if let Ok(mut player) = player_query.single_mut() {
         player.body.horizontal = 0;
+        player.body.swim_up = false;
+        if player.motion.state == PlayerMotionState::Dead {
+            return;
+        }
+
         if input.pressed(left_key) {
             player.body.horizontal -= 1;
         }
@@ -294,8 +450,29 @@ fn handle_player_input(
             player.body.horizontal += 1;
         }
 
-        if input.just_pressed(jump_key) && player.physic.grounded {
+        player.body.swim_up = input.pressed(swim_key);
+
+        let can_jump = player.physic.grounded || (player.physic.in_water && !player.physic.submerged);
+        if input.just_pressed(jump_key) && can_jump {
             player.physic.jump_timer = player.physic.jump_time;
+        } else if input.just_pressed(jump_key) && player.physic.wall != 0 && player.progression.has(Ability::WallJump) {
+            player.physic.wall_jump = true;
+        } else if input.just_pressed(jump_key) && player.physic.air_jumps > 0 && player.physic.rope.is_none() && !player.physic.in_water {
+            player.physic.air_jumps -= 1;
+            player.physic.air_jump = true;
+        }
+
+        if input.just_pressed(dash_key) && player.physic.dash_cooldown <= 0. && player.progression.has(Ability::Dash) {
+            let direction = if player.body.horizontal != 0 {
+                player.body.horizontal as f32
+            } else if player.physic.velocity.x.abs() > f32::EPSILON {
+                player.physic.velocity.x.signum()
+            } else {
+                1.
+            };
+            player.physic.dash_direction = direction;
+            player.physic.dash_timer = tuning.0.dash_time;
+            player.physic.dash_cooldown = tuning.0.dash_cooldown;
         }
 
         if input.just_released(jump_key) {
@@ -305,63 +482,178 @@ fn handle_player_input(
 }
 
 #[coverage(off)]
-fn update_physics(
+pub(crate) fn update_physics(
     time : Res<Time<Fixed>>,
-    mut player_query : Query<(&mut KinematicCharacterController, &mut Player)>,
+    tuning: Res<PlayerTuning>,
+    mut player_query : Query<(Entity, &Transform, &mut KinematicCharacterController, &mut Player, Has<HitStop>)>,
+    mut writer: EventWriter<AirJumped>,
 ) {
-    for(mut kcc, mut player) in player_query.iter_mut() {
-        player.physic.velocity.x = player.body.horizontal as f32 * player.physic.speed;
+    for(entity, transform, mut kcc, mut player, hit_stop) in player_query.iter_mut() {
+        // The rope service moves the player while a rope is held
+        if player.physic.rope.is_some() || hit_stop {
+            continue;
+        }
+
+        let dt = time.delta_secs();
+        let surface = player.physic.surface;
+        let rates = if player.physic.grounded {
+            HorizontalRates {
+                acceleration: player.physic.ground_acceleration * surface.acceleration,
+                deceleration: player.physic.ground_deceleration * surface.deceleration,
+                turn_around: player.physic.ground_turn_around * surface.acceleration,
+            }
+        } else {
+            HorizontalRates {
+                acceleration: player.physic.air_acceleration,
+                deceleration: player.physic.air_deceleration,
+                turn_around: player.physic.air_turn_around,
+            }
+        };
+        let water_speed = if player.physic.in_water { tuning.0.water_speed_scale } else { 1.0 };
+        player.physic.velocity.x = integrate_horizontal(
+            player.physic.velocity.x,
+            player.body.horizontal as f32,
+            player.physic.speed * surface.speed * water_speed,
+            &rates,
+            dt
+        );
+
+        if player.physic.grounded && surface.bounce > 0.0 {
+            player.physic.grounded = false;
+            player.physic.velocity.y = surface.bounce;
+        }
 
         if player.physic.jump_timer > 0. && player.physic.grounded {
             let jump_force = player.physic.jump_force;
             player.physic.grounded = false;
             player.physic.velocity.y = jump_force;
+        } else if player.physic.jump_timer > 0. && player.physic.in_water && !player.physic.submerged {
+            // Jump out of the water from the surface
+            player.physic.velocity.y = player.physic.jump_force;
+            player.physic.jump_timer = 0.;
+        }
+
+        if player.physic.wall_jump {
+            // Jump away from the wall the player is pressed against
+            let push = -(player.physic.wall as f32) * tuning.0.wall_jump_push;
+            player.physic.wall_jump = false;
+            player.physic.wall = 0;
+            player.physic.air_jumping = false;
+            player.physic.velocity = Vec2::new(push, player.physic.jump_force);
         }
 
-        if !player.physic.grounded {
-            player.physic.velocity.y -= GRAVITY * time.delta_secs();
+        if player.physic.air_jump {
+            player.physic.air_jump = false;
+            player.physic.air_jumping = true;
+            player.physic.velocity.y = player.physic.jump_force * tuning.0.air_jump_scale;
+            writer.write(AirJumped {
+                entity,
+                position: transform.translation.truncate(),
+                jumps_left: player.physic.air_jumps,
+            });
         }
 
-        let max_fall = 1200.0;
+        player.physic.dash_cooldown = (player.physic.dash_cooldown - dt).max(0.);
+        if player.physic.dash_timer > 0. {
+            player.physic.dash_timer = (player.physic.dash_timer - dt).max(0.);
+            player.physic.velocity = Vec2::new(player.physic.dash_direction * tuning.0.dash_speed, 0.);
+        } else if player.physic.in_water {
+            apply_water_forces(&mut player, &tuning.0, dt);
+        } else if !player.physic.grounded {
+            player.physic.velocity.y -= tuning.0.gravity * dt;
+        }
+
+        // The airborne jump ends at the peak
+        if player.physic.velocity.y <= 0. {
+            player.physic.air_jumping = false;
+        }
+
+        let max_fall = tuning.0.max_fall_speed;
         if player.physic.velocity.y < -max_fall {
             player.physic.velocity.y = -max_fall;
         }
 
-        let motion = player.physic.velocity * time.delta_secs();
+        let motion = player.physic.velocity * dt;
         kcc.translation = Some(motion);
-        player.physic.jump_timer -= time.delta_secs();
+        player.physic.jump_timer -= dt;
         if player.physic.jump_timer < 0.0 { player.physic.jump_timer = 0.0; }
     }
 }
 
+/// Applies the reduced gravity, the buoyancy and the swim up action of the player
+/// inside a water region.
+#[coverage(off)]
+fn apply_water_forces(player: &mut Player, config: &PlayerConfig, dt: f32) {
+    let physic = &mut player.physic;
+    if !physic.grounded {
+        physic.velocity.y -= config.gravity * config.water_gravity_scale * dt;
+    }
+
+    if physic.submerged {
+        physic.velocity.y += config.buoyancy * dt;
+    }
+
+    if player.body.swim_up {
+        physic.grounded = false;
+        physic.velocity.y = move_towards(physic.velocity.y, config.swim_up_speed, config.buoyancy * 4.0 * dt);
+    }
+
+    // Water drag, the player can not rise faster than swimming up or sink fast
+    physic.velocity.y = physic.velocity.y.clamp(-config.max_sink_speed, config.swim_up_speed.max(physic.jump_force));
+}
+
 #[coverage(off)]
-fn handle_collisions(
-    mut query: Query<(&KinematicCharacterControllerOutput, &mut Player)>,
+pub(crate) fn handle_collisions(
+    mut query: Query<(Entity, &KinematicCharacterControllerOutput, &mut Player)>,
+    mut writer: EventWriter<Landed>,
 ) {
-    for (kcc_out, mut player) in query.iter_mut() {
+    for (entity, kcc_out, mut player) in query.iter_mut() {
         let was_grounded = player.physic.grounded;
+        let impact_speed = (-player.physic.velocity.y).max(0.);
         player.physic.grounded = kcc_out.grounded;
         if player.physic.grounded && player.physic.velocity.y < 0. {
             player.physic.velocity.y = 0.;
         }
 
-        let _ = was_grounded;
+        if !was_grounded && player.physic.grounded {
+            writer.write(Landed { entity, impact_speed });
+        }
+
+        // The airborne jumps are only refilled with an unlocked double jump
+        if player.physic.grounded {
+            player.physic.air_jumping = false;
+            player.physic.air_jumps = if player.progression.has(Ability::DoubleJump) { player.physic.max_air_jumps } else { 0 };
+        }
+
+        // Blocked by a wall, drop the stored momentum and end a dash
+        let blocked = kcc_out.desired_translation.x.abs() > f32::EPSILON && kcc_out.effective_translation.x.abs() <= f32::EPSILON;
+        if blocked {
+            player.physic.velocity.x = 0.;
+            player.physic.dash_timer = 0.;
+        }
+
+        player.physic.wall = if blocked && !player.physic.grounded {
+            kcc_out.desired_translation.x.signum() as i32
+        } else {
+            0
+        };
     }
 }
 
+/// Builds the colliders of all tiles, a reloaded map replaces the previous colliders.
 #[coverage(off)]
-fn build_tile_colliders_once(
+fn build_tile_colliders(
     mut commands: Commands,
-    mut built: ResMut<CollisionBuilt>,
+    mut colliders: ResMut<TileColliders>,
     level_data: Res<LevelData>,
 ) {
-    if built.0 { return; }
     let Some(map) = level_data.map.as_ref() else { return; };
-    built.0 = true;
+    if let Some(previous) = colliders.0.take() {
+        commands.entity(previous).try_despawn();
+    }
 
     let tw = map.tile_width as f32;
     let th = map.tile_height as f32;
-    let mw = map.width as i32;
     let mh = map.height as i32;
 
     let parent = commands.spawn((
@@ -371,113 +663,112 @@ fn build_tile_colliders_once(
         Visibility::Visible,
         InheritedVisibility::VISIBLE,
     )).id();
+    colliders.0 = Some(parent);
 
     for layer in map.layers() {
         let LayerType::Tiles(tile_layer) = layer.layer_type() else { continue };
-        let TileLayer::Finite(ld) = tile_layer else { continue };
-
-        for x in 0..mw {
-            for y in 0..mh {
-                let tx = x;
-                let ty_inv = mh - 1 - y;
-                let Some(tile) = ld.get_tile(tx, ty_inv) else { continue };
-
-                let ts_index = tile.tileset_index();
-                let tileset = &map.tilesets()[ts_index];
-                let id = tile.id();
-
-                let mut spawned_any = false;
-
-                if let Some(tile_ref) = tileset.get_tile(id) {
-                    if let Some(ol) = tile_ref.collision.as_ref() {
-                        for obj in ol.object_data() {
-                            match &obj.shape {
-                                ObjectShape::Rect { width, height } => {
-                                    let (cx, cy) = world_center_for_rect(tx, ty_inv, *width, *height, obj.x, obj.y, tw, th, mh);
-                                    commands.spawn((
-                                        Name::new("TileRect"),
-                                        RigidBody::Fixed,
-                                        Collider::cuboid(*width * 0.5, *height * 0.5),
-                                        Transform::from_xyz(cx, cy, 0.0),
-                                        GlobalTransform::IDENTITY,
-                                        Visibility::Visible,
-                                        InheritedVisibility::VISIBLE,
-                                        ChildOf(parent),
-                                    ));
-                                    spawned_any = true;
-                                }
-                                ObjectShape::Ellipse { width, height } => {
-                                    let r = width.min(*height) * 0.5;
-                                    let (cx, cy) = world_center_for_rect(tx, ty_inv, *width, *height, obj.x, obj.y, tw, th, mh);
-                                    commands.spawn((
-                                        Name::new("TileEllipse"),
+
+        for (cell, tile) in layer_tiles(&tile_layer) {
+            let (tx, ty_inv) = (cell.x, cell.y);
+            let (x, y) = (tx, mh - 1 - ty_inv);
+
+            let ts_index = tile.tileset_index();
+            let tileset = &map.tilesets()[ts_index];
+            let id = tile.id();
+
+            let tile_ref = tileset.get_tile(id);
+            let material = tile_ref.as_ref().and_then(|t| SurfaceMaterial::from_properties(&t.properties));
+            let mut spawned = Vec::new();
+
+            if let Some(ol) = tile_ref.as_ref().and_then(|t| t.collision.as_ref()) {
+                for obj in ol.object_data() {
+                    match &obj.shape {
+                        ObjectShape::Rect { width, height } => {
+                            let (cx, cy) = world_center_for_rect(tx, ty_inv, *width, *height, obj.x, obj.y, tw, th, mh);
+                            spawned.push(commands.spawn((
+                                Name::new("TileRect"),
+                                RigidBody::Fixed,
+                                Collider::cuboid(*width * 0.5, *height * 0.5),
+                                Transform::from_xyz(cx, cy, 0.0),
+                                GlobalTransform::IDENTITY,
+                                Visibility::Visible,
+                                InheritedVisibility::VISIBLE,
+                                ChildOf(parent),
+                            )).id());
+                        }
+                        ObjectShape::Ellipse { width, height } => {
+                            let r = width.min(*height) * 0.5;
+                            let (cx, cy) = world_center_for_rect(tx, ty_inv, *width, *height, obj.x, obj.y, tw, th, mh);
+                            spawned.push(commands.spawn((
+                                Name::new("TileEllipse"),
+                                RigidBody::Fixed,
+                                Collider::ball(r),
+                                Transform::from_xyz(cx, cy, 0.0),
+                                GlobalTransform::IDENTITY,
+                                Visibility::Visible,
+                                InheritedVisibility::VISIBLE,
+                                ChildOf(parent),
+                            )).id());
+                        }
+                        ObjectShape::Polygon { points } => {
+                            let world = polygon_world_points(tx, ty_inv, points, obj.x, obj.y, tw, th, mh);
+                            if world.len() >= 3 {
+                                let center = centroid(&world);
+                                let local: Vec<Vec2> = world.iter().map(|p| *p - center).collect();
+                                if let Some(ch) = Collider::convex_hull(&local) {
+                                    spawned.push(commands.spawn((
+                                        Name::new("TilePoly"),
                                         RigidBody::Fixed,
-                                        Collider::ball(r),
-                                        Transform::from_xyz(cx, cy, 0.0),
+                                        ch,
+                                        Transform::from_xyz(center.x, center.y, 0.0),
                                         GlobalTransform::IDENTITY,
                                         Visibility::Visible,
                                         InheritedVisibility::VISIBLE,
                                         ChildOf(parent),
-                                    ));
-                                    spawned_any = true;
+                                    )).id());
                                 }
-                                ObjectShape::Polygon { points } => {
-                                    let world = polygon_world_points(tx, ty_inv, points, obj.x, obj.y, tw, th, mh);
-                                    if world.len() >= 3 {
-                                        let center = centroid(&world);
-                                        let local: Vec<Vec2> = world.iter().map(|p| *p - center).collect();
-                                        if let Some(ch) = Collider::convex_hull(&local) {
-                                            commands.spawn((
-                                                Name::new("TilePoly"),
-                                                RigidBody::Fixed,
-                                                ch,
-                                                Transform::from_xyz(center.x, center.y, 0.0),
-                                                GlobalTransform::IDENTITY,
-                                                Visibility::Visible,
-                                                InheritedVisibility::VISIBLE,
-                                                ChildOf(parent),
-                                            ));
-                                            spawned_any = true;
-                                        }
-                                    }
-                                }
-                                ObjectShape::Polyline { points } => {
-                                    let world = polygon_world_points(tx, ty_inv, points, obj.x, obj.y, tw, th, mh);
-                                    if world.len() >= 2 {
-                                        let center = centroid(&world);
-                                        let local: Vec<Vec2> = world.iter().map(|p| *p - center).collect();
-                                        commands.spawn((
-                                            Name::new("TilePolyline"),
-                                            RigidBody::Fixed,
-                                            Collider::polyline(local, None),
-                                            Transform::from_xyz(center.x, center.y, 0.0),
-                                            GlobalTransform::IDENTITY,
-                                            Visibility::Visible,
-                                            InheritedVisibility::VISIBLE,
-                                            ChildOf(parent),
-                                        ));
-                                        spawned_any = true;
-                                    }
-                                }
-                                _ => { warn!("Unhandled collision shape"); }
                             }
                         }
+                        ObjectShape::Polyline { points } => {
+                            let world = polygon_world_points(tx, ty_inv, points, obj.x, obj.y, tw, th, mh);
+                            if world.len() >= 2 {
+                                let center = centroid(&world);
+                                let local: Vec<Vec2> = world.iter().map(|p| *p - center).collect();
+                                spawned.push(commands.spawn((
+                                    Name::new("TilePolyline"),
+                                    RigidBody::Fixed,
+                                    Collider::polyline(local, None),
+                                    Transform::from_xyz(center.x, center.y, 0.0),
+                                    GlobalTransform::IDENTITY,
+                                    Visibility::Visible,
+                                    InheritedVisibility::VISIBLE,
+                                    ChildOf(parent),
+                                )).id());
+                            }
+                        }
+                        _ => { warn!("Unhandled collision shape"); }
                     }
                 }
+            }
+
+            if spawned.is_empty() && layer.name == "Collision" {
+                let cx = (x as f32 + 0.5) * tw;
+                let cy = (y as f32 + 0.5) * th;
+                spawned.push(commands.spawn((
+                    Name::new("CollisionBox"),
+                    RigidBody::Fixed,
+                    Collider::cuboid(tw * 0.5, th * 0.5),
+                    Transform::from_xyz(cx, cy, 0.0),
+                    GlobalTransform::IDENTITY,
+                    Visibility::Visible,
+                    InheritedVisibility::VISIBLE,
+                    ChildOf(parent),
+                )).id());
+            }
 
-                if !spawned_any && layer.name == "Collision" {
-                    let cx = (x as f32 + 0.5) * tw;
-                    let cy = (y as f32 + 0.5) * th;
-                    commands.spawn((
-                        Name::new("CollisionBox"),
-                        RigidBody::Fixed,
-                        Collider::cuboid(tw * 0.5, th * 0.5),
-                        Transform::from_xyz(cx, cy, 0.0),
-                        GlobalTransform::IDENTITY,
-                        Visibility::Visible,
-                        InheritedVisibility::VISIBLE,
-                        ChildOf(parent),
-                    ));
+            if let Some(material) = material {
+                for entity in spawned {
+                    commands.entity(entity).insert(material);
                 }
             }
         }
@@ -493,6 +784,7 @@ fn centroid(pts: &[Vec2]) -> Vec2 {
 }
 
 #[coverage(off)]
+#[allow(clippy::too_many_arguments)]
 fn world_center_for_rect(tx: i32, ty_inv: i32, w: f32, h: f32, ox: f32, oy: f32, tw: f32, th: f32, mh: i32) -> (f32, f32) {
     let x0 = tx as f32 * tw + ox + w * 0.5;
     let y0 = (mh as f32 - 1.0 - ty_inv as f32) * th + (th - (oy + h * 0.5));
@@ -500,6 +792,7 @@ fn world_center_for_rect(tx: i32, ty_inv: i32, w: f32, h: f32, ox: f32, oy: f32,
 }
 
 #[coverage(off)]
+#[allow(clippy::too_many_arguments)]
 fn polygon_world_points(tx: i32, ty_inv: i32, pts: &[(f32, f32)], ox: f32, oy: f32, tw: f32, th: f32, mh: i32) -> Vec<Vec2> {
     let base_x = tx as f32 * tw + ox;
     let base_y = (mh as f32 - 1.0 - ty_inv as f32) * th + (th - oy);
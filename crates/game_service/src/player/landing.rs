@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use game_core::player::{Landed, Player, PlayerTuning};
+use game_core::states::AppState;
+use game_logic::movement::fall_damage;
+use crate::player::init::handle_collisions;
+use crate::player::motion::update_motion_state;
+
+pub struct PlayerLandingService;
+
+impl Plugin for PlayerLandingService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, apply_landing
+            .after(handle_collisions)
+            .before(update_motion_state)
+            .run_if(in_state(AppState::Preload)));
+    }
+}
+
+/// Reacts on [`Landed`] events. Hard landings keep the player longer in the landing
+/// state and impacts above the fall damage threshold hurt the player.
+#[coverage(off)]
+fn apply_landing(
+    tuning: Res<PlayerTuning>,
+    mut reader: EventReader<Landed>,
+    mut player_query: Query<&mut Player>,
+) {
+    for landed in reader.read() {
+        let Ok(mut player) = player_query.get_mut(landed.entity) else { continue };
+
+        player.motion.land_time = if landed.impact_speed >= tuning.0.hard_landing_speed {
+            tuning.0.hard_land_time
+        } else {
+            tuning.0.land_time
+        };
+
+        let damage = fall_damage(landed.impact_speed, tuning.0.fall_damage_threshold, tuning.0.fall_damage_scale);
+        if damage > 0 {
+            player.stats.health -= damage;
+            player.motion.hurt_timer = tuning.0.hurt_time;
+            debug!("Fall damage {} (impact {:.0})", damage, landed.impact_speed);
+        }
+    }
+}
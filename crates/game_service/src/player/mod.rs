@@ -1,7 +1,23 @@
+mod attack;
 mod init;
+mod landing;
+mod motion;
+mod progression;
+mod rope;
+mod stats;
+mod surface;
+mod water;
 
 use bevy::prelude::*;
+use crate::player::attack::PlayerAttackService;
 use crate::player::init::PlayerInitService;
+use crate::player::landing::PlayerLandingService;
+use crate::player::motion::PlayerMotionService;
+use crate::player::progression::PlayerProgressionService;
+use crate::player::rope::PlayerRopeService;
+use crate::player::stats::PlayerStatsService;
+use crate::player::surface::PlayerSurfaceService;
+use crate::player::water::PlayerWaterService;
 
 pub struct PlayerServiceImpl;
 
@@ -9,6 +25,6 @@ impl Plugin for PlayerServiceImpl {
     
     #[coverage(off)]
     fn build(&self, app: &mut App) {
-        app.add_plugins(PlayerInitService);
+        app.add_plugins((PlayerAttackService, PlayerInitService, PlayerLandingService, PlayerMotionService, PlayerProgressionService, PlayerRopeService, PlayerStatsService, PlayerSurfaceService, PlayerWaterService));
     }
 }
\ No newline at end of file
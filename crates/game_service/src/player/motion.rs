@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use game_core::combat::Attacker;
+use game_core::player::{Player, PlayerMotionChanged};
+use game_core::states::AppState;
+use game_logic::motion::next_motion_state;
+use crate::player::init::handle_collisions;
+
+pub struct PlayerMotionService;
+
+impl Plugin for PlayerMotionService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, update_motion_state
+            .after(handle_collisions)
+            .run_if(in_state(AppState::Preload)));
+    }
+}
+
+#[coverage(off)]
+pub(crate) fn update_motion_state(
+    time: Res<Time<Fixed>>,
+    mut player_query: Query<(Entity, &mut Player, Option<&Attacker>)>,
+    mut writer: EventWriter<PlayerMotionChanged>,
+) {
+    for (entity, mut player, attacker) in player_query.iter_mut() {
+        let dt = time.delta_secs();
+        player.motion.time_in_state += dt;
+        player.motion.hurt_timer = (player.motion.hurt_timer - dt).max(0.0);
+
+        let attacking = attacker.is_some_and(Attacker::is_attacking);
+        let next = next_motion_state(&player, attacking);
+        if next == player.motion.state {
+            continue;
+        }
+
+        let from = player.motion.state;
+        player.motion.previous = from;
+        player.motion.state = next;
+        player.motion.time_in_state = 0.0;
+
+        writer.write(PlayerMotionChanged { entity, from, to: next });
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use game_core::enemy::EnemyDefeated;
+use game_core::player::Player;
+use game_core::player::progression::{AbilityUnlocked, GainExperience, LevelUp, ProgressionConfig};
+use game_core::states::AppState;
+use game_logic::progression::add_experience;
+
+pub struct PlayerProgressionService;
+
+impl Plugin for PlayerProgressionService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+            grant_enemy_experience,
+            apply_experience.after(grant_enemy_experience),
+        ).run_if(in_state(AppState::Preload)));
+    }
+}
+
+#[coverage(off)]
+fn grant_enemy_experience(
+    mut reader: EventReader<EnemyDefeated>,
+    player_query: Query<Entity, With<Player>>,
+    mut writer: EventWriter<GainExperience>,
+) {
+    let Ok(player) = player_query.single() else { return };
+    for defeated in reader.read() {
+        if defeated.xp > 0 {
+            writer.write(GainExperience { entity: player, amount: defeated.xp });
+        }
+    }
+}
+
+/// Adds the experience and applies the stat growth and the unlocks of every reached
+/// level. The health growth also heals the player by the same amount.
+#[coverage(off)]
+fn apply_experience(
+    config: Res<ProgressionConfig>,
+    mut reader: EventReader<GainExperience>,
+    mut player_query: Query<&mut Player>,
+    mut level_writer: EventWriter<LevelUp>,
+    mut ability_writer: EventWriter<AbilityUnlocked>,
+) {
+    for gain in reader.read() {
+        let Ok(mut player) = player_query.get_mut(gain.entity) else { continue };
+
+        for level in add_experience(&mut player.progression, &config, gain.amount) {
+            let Some(step) = config.step(level) else { continue };
+            player.base_stats.health += step.health;
+            player.base_stats.damage += step.damage;
+            player.base_stats.defense += step.defense;
+            player.stats.health += step.health;
+
+            if let Some(ability) = step.unlock && player.progression.unlock(ability) {
+                info!("Unlocked ability {:?}", ability);
+                ability_writer.write(AbilityUnlocked { entity: gain.entity, ability });
+            }
+
+            info!("Player reached level {}", level);
+            level_writer.write(LevelUp { entity: gain.entity, level });
+        }
+    }
+}
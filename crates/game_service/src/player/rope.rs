@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use game_core::config::GlobalConfig;
+use game_core::player::{Player, PlayerMotionState, PlayerTuning};
+use game_core::states::AppState;
+use game_core::world::props::RopeSegment;
+use crate::player::init::{handle_player_input, update_physics};
+
+pub struct PlayerRopeService;
+
+impl Plugin for PlayerRopeService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, handle_rope_input
+                .after(handle_player_input)
+                .run_if(in_state(AppState::Preload)))
+            .add_systems(FixedUpdate, swing_on_rope
+                .after(update_physics)
+                .run_if(in_state(AppState::Preload)));
+    }
+}
+
+/// Grabs the closest rope segment in reach or releases the held rope. While holding a
+/// rope the jump key releases it with an extra jump impulse. On release the velocity of
+/// the segment is carried into the player velocity.
+#[coverage(off)]
+#[allow(clippy::type_complexity)]
+fn handle_rope_input(
+    input: Res<ButtonInput<KeyCode>>,
+    global_config: Res<GlobalConfig>,
+    tuning: Res<PlayerTuning>,
+    mut player_query: Query<(&Transform, &mut Player)>,
+    mut segment_query: Query<(Entity, &GlobalTransform, &RopeSegment, Option<&Velocity>, Option<&mut ExternalForce>)>,
+) {
+    let Ok((transform, mut player)) = player_query.single_mut() else { return; };
+    if player.motion.state == PlayerMotionState::Dead {
+        return;
+    }
+
+    let grab = input.just_pressed(global_config.input_config.get_grab_key());
+    let jump = input.just_pressed(global_config.input_config.get_jump_key());
+
+    if let Some(held) = player.physic.rope {
+        let released = grab || jump || !segment_query.contains(held);
+        if !released {
+            return;
+        }
+
+        player.physic.rope = None;
+        if let Ok((_, _, _, velocity, force)) = segment_query.get_mut(held) {
+            if let Some(velocity) = velocity {
+                player.physic.velocity = velocity.linvel;
+            }
+            if let Some(mut force) = force {
+                *force = ExternalForce::default();
+            }
+        }
+
+        if jump {
+            player.physic.velocity.y = player.physic.velocity.y.max(0.0)
+                + player.physic.jump_force * tuning.0.rope_jump_scale;
+        }
+        return;
+    }
+
+    if !grab {
+        return;
+    }
+
+    let hands = transform.translation.truncate() + Vec2::new(0.0, player.body.half_size.y * 0.5);
+    let closest = segment_query.iter()
+        .filter(|(_, _, segment, _, _)| segment.grabbable)
+        .map(|(entity, segment_transform, _, _, _)| (entity, segment_transform.translation().truncate().distance(hands)))
+        .filter(|(_, distance)| *distance <= tuning.0.grab_radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+
+    if let Some((entity, _)) = closest {
+        player.physic.rope = Some(entity);
+        player.physic.grounded = false;
+        player.physic.jump_timer = 0.0;
+    }
+}
+
+/// Keeps the player at the held segment and applies the swing input and the
+/// weight of the player to it.
+#[coverage(off)]
+fn swing_on_rope(
+    tuning: Res<PlayerTuning>,
+    mut player_query: Query<(&Transform, &mut KinematicCharacterController, &Player)>,
+    mut segment_query: Query<(&GlobalTransform, Option<&mut ExternalForce>), With<RopeSegment>>,
+) {
+    for (transform, mut kcc, player) in player_query.iter_mut() {
+        let Some(held) = player.physic.rope else { continue };
+        let Ok((segment_transform, force)) = segment_query.get_mut(held) else { continue };
+
+        let hands_offset = Vec2::new(0.0, player.body.half_size.y * 0.5);
+        let target = segment_transform.translation().truncate() - hands_offset;
+        kcc.translation = Some(target - transform.translation.truncate());
+
+        if let Some(mut force) = force {
+            force.force = Vec2::new(
+                player.body.horizontal as f32 * tuning.0.swing_force,
+                -tuning.0.mass * tuning.0.gravity * tuning.0.rope_weight_scale
+            );
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use game_core::player::Player;
+use game_core::states::AppState;
+use game_core::stats::{ApplyStatusEffect, StatChanged, StatKind, StatusEffects};
+use game_logic::stats::{compute_effect_stat, tick_effects};
+use crate::player::init::update_physics;
+
+pub struct PlayerStatsService;
+
+impl Plugin for PlayerStatsService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_status_effects.run_if(in_state(AppState::Preload)))
+
+            .add_systems(FixedUpdate, (
+                tick_status_effects,
+                update_player_stats.after(tick_status_effects).before(update_physics),
+            ).run_if(in_state(AppState::Preload)));
+    }
+}
+
+#[coverage(off)]
+fn apply_status_effects(
+    mut reader: EventReader<ApplyStatusEffect>,
+    mut effects_query: Query<&mut StatusEffects>,
+) {
+    for request in reader.read() {
+        if let Ok(mut effects) = effects_query.get_mut(request.target) {
+            debug!("Apply status effect '{}'", request.effect.name);
+            effects.apply(request.effect.clone());
+        }
+    }
+}
+
+/// Counts down all effects of the player and deals the damage over time.
+#[coverage(off)]
+fn tick_status_effects(
+    time: Res<Time<Fixed>>,
+    mut player_query: Query<(&mut Player, &mut StatusEffects)>,
+) {
+    for (mut player, mut effects) in player_query.iter_mut() {
+        if effects.effects.is_empty() {
+            continue;
+        }
+        let damage = tick_effects(&mut effects, time.delta_secs());
+        if damage > 0 {
+            player.stats.health -= damage;
+        }
+    }
+}
+
+/// Recomputes the stats from the base stats and all active modifiers. Speed and
+/// jump force are written to the [`game_core::player::PlayerPhysic`].
+#[coverage(off)]
+fn update_player_stats(
+    mut player_query: Query<(Entity, &mut Player, &StatusEffects)>,
+    mut writer: EventWriter<StatChanged>,
+) {
+    for (entity, mut player, effects) in player_query.iter_mut() {
+        let base = &player.base_stats;
+        let max_health = compute_effect_stat(base.health as f32, StatKind::Health, effects).round() as i32;
+        let damage = compute_effect_stat(base.damage as f32, StatKind::Damage, effects).round() as i32;
+        let defense = compute_effect_stat(base.defense as f32, StatKind::Defense, effects).round() as i32;
+        let speed = compute_effect_stat(base.speed, StatKind::Speed, effects);
+        let jump_force = compute_effect_stat(base.jump_force, StatKind::JumpForce, effects);
+
+        let stats = &mut player.stats;
+        let changes = [
+            (StatKind::Health, stats.max_health as f32, max_health as f32),
+            (StatKind::Damage, stats.damage as f32, damage as f32),
+            (StatKind::Defense, stats.defense as f32, defense as f32),
+            (StatKind::Speed, stats.speed, speed),
+            (StatKind::JumpForce, stats.jump_force, jump_force),
+        ];
+        for (stat, from, to) in changes {
+            if from != to {
+                writer.write(StatChanged { entity, stat, from, to });
+            }
+        }
+
+        stats.max_health = max_health;
+        stats.damage = damage;
+        stats.defense = defense;
+        stats.speed = speed;
+        stats.jump_force = jump_force;
+        stats.health = stats.health.min(max_health);
+
+        player.physic.speed = player.stats.speed;
+        player.physic.jump_force = player.stats.jump_force;
+    }
+}
@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use game_core::player::Player;
+use game_core::states::AppState;
+use game_core::world::surface::SurfaceMaterial;
+use crate::player::init::handle_collisions;
+
+/// Extra distance below the player feet which is still seen as ground.
+const GROUND_PROBE: f32 = 4.0;
+
+pub struct PlayerSurfaceService;
+
+impl Plugin for PlayerSurfaceService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, detect_ground_surface
+            .after(handle_collisions)
+            .run_if(in_state(AppState::Preload)));
+    }
+}
+
+/// Casts a ray below the grounded player and stores the [`SurfaceMaterial`] of the hit
+/// collider at the player. Colliders without material count as default ground.
+#[coverage(off)]
+fn detect_ground_surface(
+    rapier_context: ReadRapierContext,
+    surface_query: Query<&SurfaceMaterial>,
+    mut player_query: Query<(Entity, &GlobalTransform, &mut Player)>,
+) {
+    let Ok(context) = rapier_context.single() else { return; };
+
+    for (entity, transform, mut player) in player_query.iter_mut() {
+        if !player.physic.grounded {
+            player.physic.surface = SurfaceMaterial::default();
+            continue;
+        }
+
+        let origin = transform.translation().truncate();
+        let distance = player.body.half_size.y + GROUND_PROBE;
+        let filter = QueryFilter::default().exclude_collider(entity).exclude_sensors();
+        let hit = context.with_query_pipeline(filter, |pipeline| {
+            pipeline.cast_ray(origin, -Vec2::Y, distance, true)
+        });
+
+        player.physic.surface = hit
+            .and_then(|(collider, _)| surface_query.get(collider).ok().copied())
+            .unwrap_or_default();
+    }
+}
@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+use tiled::{LayerType, ObjectShape};
+use game_core::player::{Player, PlayerTuning};
+use game_core::states::AppState;
+use game_core::tiled::{LevelData, ObjectLayers};
+use game_core::tiled::properties::{ObjectShapeExt, PropertyValueExt};
+use game_core::world::tiled_to_world_position;
+use game_core::world::water::{SplashKind, WaterRegions, WaterSplash};
+use crate::player::init::update_physics;
+
+pub struct PlayerWaterService;
+
+impl Plugin for PlayerWaterService {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Preload), init_water_loader)
+            .add_systems(Update, build_liquid_tile_regions
+                .run_if(in_state(AppState::Preload))
+                .run_if(resource_changed::<LevelData>))
+            .add_systems(FixedUpdate, (detect_water, update_breath.after(detect_water))
+                .before(update_physics)
+                .run_if(in_state(AppState::Preload)));
+    }
+}
+
+#[coverage(off)]
+fn init_water_loader(
+    mut object_layers: ResMut<ObjectLayers>,
+    mut commands: Commands
+) {
+    object_layers.loader_systems.insert(String::from("Water"), commands.register_system(init_water_objects));
+}
+
+/// Creates a water region for every rectangle of the `Water` object layer and replaces
+/// the regions of a previous map.
+#[coverage(off)]
+fn init_water_objects(
+    object_layers: Res<ObjectLayers>,
+    level_data: Res<LevelData>,
+    mut water: ResMut<WaterRegions>,
+) {
+    let Some(map) = level_data.map.as_ref() else { return; };
+    let Some(objects) = object_layers.layer_data.get("Water") else { return; };
+
+    water.object_regions.clear();
+    for object in objects {
+        if !matches!(object.shape, ObjectShape::Rect { .. }) {
+            warn!("Water object '{}' is not a rectangle", object.name);
+            continue;
+        }
+
+        let top_left = tiled_to_world_position(Vec2::new(object.x, object.y), map);
+        let height = object.shape.get_height();
+        water.object_regions.push(Rect::new(
+            top_left.x,
+            top_left.y - height,
+            top_left.x + object.shape.get_width(),
+            top_left.y
+        ));
+    }
+}
+
+/// Creates water regions for all tiles with `liquid = true`. Neighbouring tiles of
+/// a row are merged into one region. Runs again for a reloaded map, which also drops
+/// the object regions of the previous map if the new one has no `Water` layer.
+#[coverage(off)]
+fn build_liquid_tile_regions(
+    level_data: Res<LevelData>,
+    object_layers: Res<ObjectLayers>,
+    mut water: ResMut<WaterRegions>,
+) {
+    water.tile_regions.clear();
+    if !object_layers.layer_data.contains_key("Water") {
+        water.object_regions.clear();
+    }
+    let Some(map) = level_data.map.as_ref() else { return; };
+
+    let tw = map.tile_width as f32;
+    let th = map.tile_height as f32;
+    let mh = map.height as i32;
+    let bounds = level_data.bounds;
+
+    for layer in map.layers() {
+        let LayerType::Tiles(tile_layer) = layer.layer_type() else { continue };
+
+        for ty_inv in bounds.min.y..bounds.max.y {
+            let y = mh - 1 - ty_inv;
+            let mut run_start: Option<i32> = None;
+
+            for x in bounds.min.x..=bounds.max.x {
+                let liquid = x < bounds.max.x && tile_layer.get_tile(x, ty_inv)
+                    .and_then(|tile| tile.get_tile())
+                    .and_then(|tile| tile.properties.get("liquid").map(|v| v.bool_or(false)))
+                    .unwrap_or(false);
+
+                match (liquid, run_start) {
+                    (true, None) => run_start = Some(x),
+                    (false, Some(start)) => {
+                        water.tile_regions.push(Rect::new(start as f32 * tw, y as f32 * th, x as f32 * tw, (y + 1) as f32 * th));
+                        run_start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    if !water.tile_regions.is_empty() {
+        debug!("Water tile regions: {}", water.tile_regions.len());
+    }
+}
+
+/// Checks the body and the head of the player against the water regions and
+/// sends a [`WaterSplash`] when the player enters or leaves the water.
+#[coverage(off)]
+fn detect_water(
+    water: Res<WaterRegions>,
+    mut player_query: Query<(Entity, &Transform, &mut Player)>,
+    mut writer: EventWriter<WaterSplash>,
+) {
+    for (entity, transform, mut player) in player_query.iter_mut() {
+        let center = transform.translation.truncate();
+        let head = center + Vec2::new(0.0, player.body.half_size.y * 0.6);
+
+        let was_in_water = player.physic.in_water;
+        player.physic.in_water = water.contains(center);
+        player.physic.submerged = water.contains(head);
+
+        if was_in_water != player.physic.in_water {
+            let kind = if player.physic.in_water { SplashKind::Enter } else { SplashKind::Exit };
+            writer.write(WaterSplash { entity, position: center, kind });
+        }
+    }
+}
+
+/// Drains the breath while the player is submerged and hurts the player when no
+/// breath is left. The breath refills above water.
+#[coverage(off)]
+fn update_breath(
+    time: Res<Time<Fixed>>,
+    tuning: Res<PlayerTuning>,
+    mut player_query: Query<&mut Player>,
+) {
+    let dt = time.delta_secs();
+    for mut player in player_query.iter_mut() {
+        let max_breath = player.base_stats.breath;
+        if !player.physic.submerged {
+            player.stats.breath = (player.stats.breath + tuning.0.breath_regen * dt).min(max_breath);
+            player.stats.drown_damage = 0.0;
+            continue;
+        }
+
+        player.stats.breath = (player.stats.breath - dt).max(0.0);
+        if player.stats.breath > 0.0 {
+            continue;
+        }
+
+        player.stats.drown_damage += tuning.0.drown_damage * dt;
+        let damage = player.stats.drown_damage.floor();
+        if damage >= 1.0 {
+            player.stats.drown_damage -= damage;
+            player.stats.health -= damage as i32;
+        }
+    }
+}
@@ -1,9 +1,11 @@
 #![feature(coverage_attribute)]
 
 mod level;
+mod props;
 
 use bevy::prelude::*;
 use crate::level::WorldLevelPlugin;
+use crate::props::WorldPropsPlugin;
 
 pub struct GameWorldPlugin;
 
@@ -11,9 +13,10 @@ impl Plugin for GameWorldPlugin {
     
     #[coverage(off)]
     fn build(&self, app: &mut App) {
-        app.add_plugins(
-            WorldLevelPlugin
-        );
+        app.add_plugins((
+            WorldLevelPlugin,
+            WorldPropsPlugin
+        ));
     }
     
 }
\ No newline at end of file
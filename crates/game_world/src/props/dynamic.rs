@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use tiled::ObjectShape;
+use game_core::states::AppState;
+use game_core::tiled::{LevelData, ObjectLayers};
+use game_core::tiled::objects::TiledObject;
+use game_core::tiled::properties::{ObjectShapeExt, PropertyValueExt};
+use game_core::world::props::{DynamicProp, ExperiencePickup, PressurePlate};
+use game_core::world::tiled_to_world_position;
+
+pub struct DynamicPropsModule;
+
+impl Plugin for DynamicPropsModule {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.register_type::<DynamicProp>();
+        app.register_type::<PressurePlate>();
+        app.register_type::<ExperiencePickup>();
+        app.add_systems(OnEnter(AppState::Preload), init_props_loader);
+    }
+}
+
+#[coverage(off)]
+fn init_props_loader(
+    mut object_layers: ResMut<ObjectLayers>,
+    mut commands: Commands
+) {
+    object_layers.loader_systems.insert(String::from("Props"), commands.register_system(spawn_props));
+}
+
+/// Spawns all objects of the `Props` layer. The object type decides what is spawned:
+/// - `box`: A dynamic cuboid.
+/// - `ball`: A dynamic ball, the smaller side of the object is the diameter.
+/// - `pressure_plate`: A fixed sensor which is pressed by props and the player.
+/// - `xp`: A pickup which gives the experience of the `amount` property.
+///
+/// Dynamic props read the optional properties `mass`, `friction`, `restitution` and `color`.
+#[coverage(off)]
+fn spawn_props(
+    mut commands: Commands,
+    object_layers: Res<ObjectLayers>,
+    level_data: Res<LevelData>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let Some(map) = level_data.map.as_ref() else { return; };
+    let Some(objects) = object_layers.layer_data.get("Props") else { return; };
+
+    for object in objects {
+        let (width, height) = match object.shape {
+            ObjectShape::Ellipse { width, height } => (width, height),
+            _ => (object.shape.get_width(), object.shape.get_height()),
+        };
+
+        if width <= 0.0 || height <= 0.0 {
+            warn!("Prop '{}' has no size", object.name);
+            continue;
+        }
+
+        let top_left = tiled_to_world_position(Vec2::new(object.x, object.y), map);
+        let center = top_left + Vec2::new(width * 0.5, -height * 0.5);
+        let property = |key: &str| object.properties.get(key).and_then(|v| v.as_number());
+
+        let (collider, mesh) = match object.user_type.to_ascii_lowercase().as_str() {
+            "box" => (
+                Collider::cuboid(width * 0.5, height * 0.5),
+                meshes.add(Rectangle::new(width, height))
+            ),
+            "ball" => (
+                Collider::ball(width.min(height) * 0.5),
+                meshes.add(Circle::new(width.min(height) * 0.5))
+            ),
+            "pressure_plate" => {
+                commands.spawn((
+                    Name::new(format!("PressurePlate: {}", object.name)),
+                    TiledObject,
+                    PressurePlate {
+                        name: object.name.clone(),
+                        ..default()
+                    },
+                    Transform::from_xyz(center.x, center.y, 0.0),
+                    GlobalTransform::IDENTITY,
+                    Visibility::Visible,
+                    InheritedVisibility::VISIBLE,
+                    RigidBody::Fixed,
+                    Collider::cuboid(width * 0.5, height * 0.5),
+                    Sensor,
+                    ActiveEvents::COLLISION_EVENTS,
+                    ActiveCollisionTypes::all(),
+                ));
+                continue;
+            }
+            "xp" => {
+                let amount = object.properties.get("amount").and_then(|v| v.as_u32()).unwrap_or(10);
+                commands.spawn((
+                    Name::new(format!("ExperiencePickup: {}", object.name)),
+                    TiledObject,
+                    ExperiencePickup { amount },
+                    Sprite {
+                        color: Color::srgb(0.35, 0.8, 1.0),
+                        custom_size: Some(Vec2::new(width, height)),
+                        ..default()
+                    },
+                    Transform::from_xyz(center.x, center.y, 5.0),
+                    GlobalTransform::IDENTITY,
+                    Visibility::Visible,
+                    InheritedVisibility::VISIBLE,
+                    RigidBody::Fixed,
+                    Collider::cuboid(width * 0.5, height * 0.5),
+                    Sensor,
+                    ActiveEvents::COLLISION_EVENTS,
+                    ActiveCollisionTypes::all(),
+                ));
+                continue;
+            }
+            other => {
+                warn!("Unknown prop type '{}' of '{}'", other, object.name);
+                continue;
+            }
+        };
+
+        let color = object.properties.get("color")
+            .and_then(|v| v.as_color())
+            .map(|c| Color::srgba_u8(c.red, c.green, c.blue, c.alpha))
+            .unwrap_or(Color::srgb(0.55, 0.38, 0.22));
+
+        commands.spawn((
+            (Name::new(format!("Prop: {}", object.name)), TiledObject),
+            Mesh2d(mesh),
+            MeshMaterial2d(materials.add(color)),
+            DynamicProp {
+                name: object.name.clone(),
+            },
+            Transform::from_xyz(center.x, center.y, 5.0),
+            GlobalTransform::IDENTITY,
+            Visibility::Visible,
+            InheritedVisibility::VISIBLE,
+            RigidBody::Dynamic,
+            collider,
+            ColliderMassProperties::Mass(property("mass").unwrap_or(2.0)),
+            Friction::coefficient(property("friction").unwrap_or(0.7)),
+            Restitution::coefficient(property("restitution").unwrap_or(0.0)),
+            Velocity::default(),
+            Ccd::enabled(),
+        ));
+    }
+}
@@ -0,0 +1,20 @@
+mod dynamic;
+mod pickup;
+mod pressure_plate;
+mod rope;
+
+use bevy::prelude::*;
+use crate::props::dynamic::DynamicPropsModule;
+use crate::props::pickup::PickupModule;
+use crate::props::pressure_plate::PressurePlateModule;
+use crate::props::rope::RopeModule;
+
+pub struct WorldPropsPlugin;
+
+impl Plugin for WorldPropsPlugin {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_plugins((DynamicPropsModule, PickupModule, PressurePlateModule, RopeModule));
+    }
+}
@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use game_core::player::Player;
+use game_core::player::progression::GainExperience;
+use game_core::states::AppState;
+use game_core::world::props::ExperiencePickup;
+
+pub struct PickupModule;
+
+impl Plugin for PickupModule {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, collect_pickups.run_if(in_state(AppState::Preload)));
+    }
+}
+
+/// Gives the experience of an [`ExperiencePickup`] to the player which touched it.
+#[coverage(off)]
+fn collect_pickups(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    pickup_query: Query<&ExperiencePickup>,
+    player_query: Query<(), With<Player>>,
+    mut writer: EventWriter<GainExperience>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(a, b, _) = event else { continue };
+        let (pickup_entity, player) = if pickup_query.contains(*a) { (*a, *b) } else { (*b, *a) };
+        if !player_query.contains(player) {
+            continue;
+        }
+        let Ok(pickup) = pickup_query.get(pickup_entity) else { continue };
+
+        writer.write(GainExperience { entity: player, amount: pickup.amount });
+        commands.entity(pickup_entity).try_despawn();
+    }
+}
@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use game_core::player::Player;
+use game_core::states::AppState;
+use game_core::world::props::{DynamicProp, PressurePlate, PressurePlateChanged};
+
+pub struct PressurePlateModule;
+
+impl Plugin for PressurePlateModule {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_pressure_plates.run_if(in_state(AppState::Preload)));
+    }
+}
+
+/// Counts the props and players on each [`PressurePlate`] and sends a
+/// [`PressurePlateChanged`] when the pressed state flips.
+#[coverage(off)]
+#[allow(clippy::type_complexity)]
+fn update_pressure_plates(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut plate_query: Query<&mut PressurePlate>,
+    presser_query: Query<(), Or<(With<DynamicProp>, With<Player>)>>,
+    mut writer: EventWriter<PressurePlateChanged>,
+) {
+    for event in collision_events.read() {
+        let (a, b, started) = match event {
+            CollisionEvent::Started(a, b, _) => (*a, *b, true),
+            CollisionEvent::Stopped(a, b, _) => (*a, *b, false),
+        };
+
+        let (plate_entity, other) = if plate_query.contains(a) { (a, b) } else { (b, a) };
+        if !presser_query.contains(other) {
+            continue;
+        }
+        let Ok(mut plate) = plate_query.get_mut(plate_entity) else { continue };
+
+        plate.contacts = if started { plate.contacts + 1 } else { plate.contacts.saturating_sub(1) };
+        let pressed = plate.contacts > 0;
+        if pressed != plate.pressed {
+            plate.pressed = pressed;
+            debug!("Pressure plate '{}' pressed: {}", plate.name, pressed);
+            writer.write(PressurePlateChanged {
+                entity: plate_entity,
+                name: plate.name.clone(),
+                pressed,
+            });
+        }
+    }
+}
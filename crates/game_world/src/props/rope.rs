@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use tiled::ObjectShape;
+use game_core::states::AppState;
+use game_core::tiled::{LevelData, ObjectLayers};
+use game_core::tiled::objects::TiledObject;
+use game_core::tiled::properties::PropertyValueExt;
+use game_core::world::props::{Rope, RopeSegment, ROPE_COLLISION_GROUP};
+use game_core::world::tiled_to_world_position;
+use game_logic::geometry::resample_polyline;
+
+pub struct RopeModule;
+
+impl Plugin for RopeModule {
+
+    #[coverage(off)]
+    fn build(&self, app: &mut App) {
+        app.register_type::<Rope>();
+        app.register_type::<RopeSegment>();
+        app.add_systems(OnEnter(AppState::Preload), init_rope_loader)
+            .add_systems(Update, draw_rope_joints
+                .run_if(in_state(AppState::Preload).and(resource_exists::<DebugRenderContext>)));
+    }
+}
+
+#[coverage(off)]
+fn init_rope_loader(
+    mut object_layers: ResMut<ObjectLayers>,
+    mut commands: Commands
+) {
+    object_layers.loader_systems.insert(String::from("Ropes"), commands.register_system(spawn_ropes));
+}
+
+/// Builds a joint chain for every polyline of the `Ropes` layer. The object type
+/// `chain` creates heavier links than the default `rope`. The first point of the
+/// polyline is the fixed anchor.
+///
+/// Optional properties are `segment_length`, `mass` (per segment) and `damping`.
+#[coverage(off)]
+fn spawn_ropes(
+    mut commands: Commands,
+    object_layers: Res<ObjectLayers>,
+    level_data: Res<LevelData>,
+) {
+    let Some(map) = level_data.map.as_ref() else { return; };
+    let Some(objects) = object_layers.layer_data.get("Ropes") else { return; };
+
+    for object in objects {
+        let ObjectShape::Polyline { points } = &object.shape else {
+            warn!("Rope '{}' is not a polyline", object.name);
+            continue;
+        };
+
+        let is_chain = object.user_type.eq_ignore_ascii_case("chain");
+        let property = |key: &str| object.properties.get(key).and_then(|v| v.as_number());
+        let segment_length = property("segment_length").unwrap_or(if is_chain { 10.0 } else { 8.0 }).max(1.0);
+        let mass = property("mass").unwrap_or(if is_chain { 0.6 } else { 0.2 });
+        let damping = property("damping").unwrap_or(if is_chain { 0.2 } else { 0.6 });
+        let radius = if is_chain { 2.5 } else { 1.5 };
+
+        let world: Vec<Vec2> = points.iter()
+            .map(|(x, y)| tiled_to_world_position(Vec2::new(object.x + x, object.y + y), map))
+            .collect();
+        let positions = resample_polyline(&world, segment_length);
+        if positions.len() < 2 {
+            warn!("Rope '{}' is too short", object.name);
+            continue;
+        }
+
+        let rope = commands.spawn((
+            Name::new(format!("Rope: {}", object.name)),
+            TiledObject,
+            Transform::IDENTITY,
+            GlobalTransform::IDENTITY,
+            Visibility::Visible,
+            InheritedVisibility::VISIBLE,
+        )).id();
+
+        let groups = CollisionGroups::new(
+            Group::from_bits_truncate(ROPE_COLLISION_GROUP),
+            Group::GROUP_1
+        );
+
+        let mut segments: Vec<Entity> = Vec::with_capacity(positions.len());
+        for (index, position) in positions.iter().enumerate() {
+            let mut segment = commands.spawn((
+                Name::new(format!("RopeSegment {index}")),
+                TiledObject,
+                RopeSegment {
+                    rope,
+                    index,
+                    grabbable: index > 0,
+                },
+                Transform::from_xyz(position.x, position.y, 4.0),
+                GlobalTransform::IDENTITY,
+                Visibility::Visible,
+                InheritedVisibility::VISIBLE,
+                Collider::ball(radius),
+                groups,
+            ));
+
+            if let Some(parent) = segments.last() {
+                let joint = RevoluteJointBuilder::new()
+                    .local_anchor1(*position - positions[index - 1])
+                    .local_anchor2(Vec2::ZERO);
+                segment.insert((
+                    RigidBody::Dynamic,
+                    ColliderMassProperties::Mass(mass),
+                    Damping { linear_damping: damping, angular_damping: damping },
+                    Velocity::default(),
+                    ExternalForce::default(),
+                    ImpulseJoint::new(*parent, joint),
+                ));
+            } else {
+                segment.insert(RigidBody::Fixed);
+            }
+
+            segments.push(segment.id());
+        }
+
+        commands.entity(rope).insert(Rope {
+            name: object.name.clone(),
+            segments,
+        });
+    }
+}
+
+/// Draws the rope joints while the rapier debug render is enabled.
+#[coverage(off)]
+fn draw_rope_joints(
+    debug_context: Res<DebugRenderContext>,
+    rope_query: Query<&Rope>,
+    segment_query: Query<&GlobalTransform, With<RopeSegment>>,
+    mut gizmos: Gizmos,
+) {
+    if !debug_context.enabled { return; }
+
+    for rope in rope_query.iter() {
+        let mut previous: Option<Vec2> = None;
+        for segment in rope.segments.iter() {
+            let Ok(transform) = segment_query.get(*segment) else { continue };
+            let position = transform.translation().truncate();
+            if let Some(previous) = previous {
+                gizmos.line_2d(previous, position, Color::srgb(1.0, 0.8, 0.2));
+            }
+            gizmos.circle_2d(Isometry2d::from_translation(position), 1.5, Color::srgb(1.0, 0.3, 0.2));
+            previous = Some(position);
+        }
+    }
+}
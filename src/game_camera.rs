@@ -3,26 +3,40 @@ use bevy::render::camera::ScalingMode;
 use bevy::render::view::RenderLayers;
 use bevy::window::PrimaryWindow;
 use game_core::camera::{CameraGame, CameraUi};
-use game_core::player::Player;
+use game_core::player::{Landed, Player, PlayerTuning};
 use game_core::states::AppState;
 use game_core::tiled::LevelData;
+use game_core::world::tiled_to_world_position;
+
+/// Max camera offset in pixel at full trauma.
+const MAX_SHAKE: f32 = 6.0;
 
 pub struct GameCameraPlugin;
 
+/// Trauma based camera shake, the trauma decays over time and the offset grows
+/// with the square of it.
+#[derive(Resource, Default)]
+struct CameraShake {
+    trauma: f32,
+    offset: Vec2,
+}
+
 impl Plugin for GameCameraPlugin {
 
     #[coverage(off)]
     fn build(&self, app: &mut App) {
+        app.init_resource::<CameraShake>();
         app.add_systems(OnEnter(AppState::Preload), setup_ui_camera);
         app.add_systems(OnEnter(AppState::Preload), setup_game_camera)
-            .add_systems(Update, update_game_camera.run_if(in_state(AppState::Preload)));
+            .add_systems(Update, (shake_on_landing, update_game_camera.after(shake_on_landing))
+                .run_if(in_state(AppState::Preload)));
     }
 }
 
 #[coverage(off)]
 fn setup_game_camera(mut commands: Commands) {
     commands.spawn((
-        Camera2d::default(),
+        Camera2d,
         Camera {
             order: 0,
             ..default()
@@ -41,6 +55,7 @@ fn setup_game_camera(mut commands: Commands) {
 }
 
 #[coverage(off)]
+#[allow(clippy::if_same_then_else)]
 fn auto_zoom(win: &Window) -> f32 {
     let w = win.resolution.physical_width();
     let h = win.resolution.physical_height();
@@ -57,11 +72,13 @@ fn auto_zoom(win: &Window) -> f32 {
 }
 
 #[coverage(off)]
+#[allow(clippy::type_complexity)]
 fn update_game_camera(
     time: Res<Time>,
     mut q_cam: Query<(&Camera, &mut Transform, &mut Projection), (Without<Player>, With<CameraGame>)>,
     q_player: Query<&Transform, (Without<CameraGame>, With<Player>)>,
     level_data: Res<LevelData>,
+    mut shake: ResMut<CameraShake>,
     q_win: Query<&Window, With<PrimaryWindow>>, // <— NEU
 ) {
     let (camera, mut cam_tf, mut proj) = if let Ok(x) = q_cam.single_mut() { x } else { return };
@@ -72,7 +89,7 @@ fn update_game_camera(
     let dt = time.delta_secs();
     let t = 1.0 - (-follow_strength * dt).exp();
     let target = player_tf.translation.truncate();
-    let cam_xy = cam_tf.translation.truncate();
+    let cam_xy = cam_tf.translation.truncate() - shake.offset;
     let new_xy = cam_xy + (target - cam_xy) * t;
 
     let (map, view) = if let (Some(m), Some(v)) = (level_data.map.as_ref(), camera.logical_viewport_size()) {
@@ -80,8 +97,15 @@ fn update_game_camera(
     } else {
         return;
     };
-    let map_w = (map.width * map.tile_width) as f32;
-    let map_h = (map.height * map.tile_height) as f32;
+    // Used area of the map in world space, infinite maps can reach outside the map size
+    let bounds = level_data.bounds;
+    let tile_size = Vec2::new(map.tile_width as f32, map.tile_height as f32);
+    let area = Rect::from_corners(
+        tiled_to_world_position(IVec2::new(bounds.min.x, bounds.max.y).as_vec2() * tile_size, map),
+        tiled_to_world_position(IVec2::new(bounds.max.x, bounds.min.y).as_vec2() * tile_size, map),
+    );
+    let map_w = area.width();
+    let map_h = area.height();
     let view_w = view.x;
     let view_h = view.y;
 
@@ -95,18 +119,38 @@ fn update_game_camera(
         let half = Vec2::new(view_w, view_h) * orthographic.scale * 0.5;
 
         let mut p = new_xy;
-        if map_w > half.x * 2.0 { p.x = p.x.clamp(half.x, map_w - half.x) } else { p.x = map_w * 0.5; }
-        if map_h > half.y * 2.0 { p.y = p.y.clamp(half.y, map_h - half.y) } else { p.y = map_h * 0.5; }
+        if map_w > half.x * 2.0 { p.x = p.x.clamp(area.min.x + half.x, area.max.x - half.x) } else { p.x = area.center().x; }
+        if map_h > half.y * 2.0 { p.y = p.y.clamp(area.min.y + half.y, area.max.y - half.y) } else { p.y = area.center().y; }
 
-        cam_tf.translation.x = p.x;
-        cam_tf.translation.y = p.y;
+        let elapsed = time.elapsed_secs();
+        let amount = shake.trauma * shake.trauma * MAX_SHAKE;
+        shake.offset = Vec2::new((elapsed * 47.0).sin(), (elapsed * 59.0 + 1.3).sin()) * amount;
+        shake.trauma = (shake.trauma - dt * 1.5).max(0.0);
+
+        cam_tf.translation.x = p.x + shake.offset.x;
+        cam_tf.translation.y = p.y + shake.offset.y;
+    }
+}
+
+#[coverage(off)]
+fn shake_on_landing(
+    mut reader: EventReader<Landed>,
+    tuning: Res<PlayerTuning>,
+    mut shake: ResMut<CameraShake>,
+) {
+    for landed in reader.read() {
+        if landed.impact_speed < tuning.0.hard_landing_speed {
+            continue;
+        }
+        let strength = landed.impact_speed / tuning.0.fall_damage_threshold.max(1.0);
+        shake.trauma = (shake.trauma + strength * 0.5).min(1.0);
     }
 }
 
 #[coverage(off)]
 fn setup_ui_camera(mut commands: Commands) {
     commands.spawn((
-        Camera2d::default(),
+        Camera2d,
         Camera {
             order: 1,
             clear_color: ClearColorConfig::None,
@@ -259,6 +259,7 @@ mod manager {
     use game_core::config::GlobalConfig;
     use game_core::debug::WorldInspectorState;
     use game_core::GameCorePlugin;
+    use game_core::tiled::TiledMapHandle;
     use game_logic::GameLogicPlugin;
     use game_service::GameServicePlugin;
     use game_ui::GameUiPlugin;
@@ -290,7 +291,7 @@ mod manager {
             app.add_plugins(GameCameraPlugin);
 
             app.add_systems(Startup, setup_shadow_map);
-            app.add_systems(Update, (toggle_world_inspector, toggle_debug_system));
+            app.add_systems(Update, (toggle_world_inspector, toggle_debug_system, reload_tiled_map));
         }
     }
 
@@ -311,6 +312,27 @@ mod manager {
         }
     }
 
+    /// Reloads the map from disk, the tiled module rebuilds the level while playing. Saved
+    /// maps are reloaded on their own with the `dev` feature, this key is the fallback.
+    #[coverage(off)]
+    fn reload_tiled_map(
+        keyboard: Res<ButtonInput<KeyCode>>,
+        global_config: Res<GlobalConfig>,
+        asset_server: Res<AssetServer>,
+        maps: Query<&TiledMapHandle>,
+    ) {
+        let key = global_config.input_config.get_reload_map_key();
+        if !keyboard.just_pressed(key) {
+            return;
+        }
+        for map in maps.iter() {
+            if let Some(path) = map.0.path() {
+                info!("Reloading map {path} on {key:?}");
+                asset_server.reload(path.clone());
+            }
+        }
+    }
+
     #[coverage(off)]
     pub fn toggle_debug_system(
         mut debug_context: ResMut<DebugRenderContext>,